@@ -0,0 +1,85 @@
+//! Times `prebuild_primitive_meshes` on a scene of 1000 mesh instances, each with its own torus.
+//! Instances sharing a mesh resource only build it once, so every instance gets a distinct
+//! resource to measure the generation itself. Compare the sequential and parallel builds with
+//! `cargo bench -p bevy_godot_scene_loader --bench prebuild`
+//! and `cargo bench -p bevy_godot_scene_loader --bench prebuild --features parallel`.
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    asset::Assets,
+    ecs::{
+        system::{ResMut, RunSystemOnce},
+        world::World,
+    },
+    render::mesh::Mesh,
+};
+use bevy_godot_scene_loader::mesh::{prebuild_primitive_meshes, SceneAssetCache};
+use common::{
+    builder::{translation, SceneWorldBuilder},
+    entities::render::MeshInstanceData,
+    resources::render::TorusMeshData,
+    transform::IDENTITY_TRANSFORM,
+    EntityData, ResourceData, SceneWorld,
+};
+
+const INSTANCES: usize = 1000;
+const RUNS: u32 = 10;
+
+fn torus_scene() -> SceneWorld {
+    let mut builder = SceneWorldBuilder::new();
+    for i in 0..INSTANCES {
+        let mesh = builder.add_resource(ResourceData::TorusMesh(TorusMeshData {
+            inner_radius: 0.5 + i as f32 * 0.001,
+            outer_radius: 1.0 + i as f32 * 0.001,
+            rings: None,
+            ring_segments: None,
+            material: None,
+        }));
+        let name = format!("Ring{}", i);
+        builder = builder
+            .add_node(name.clone(), translation([i as f32 * 2.0, 0.0, 0.0]))
+            .with_child(
+                format!("{}_MeshInstance3D", name),
+                EntityData::MeshInstance3D(MeshInstanceData {
+                    mesh,
+                    visible: true,
+                    transform: IDENTITY_TRANSFORM.to_vec(),
+                    visibility_layer: None,
+                    cast_shadow: None,
+                }),
+            );
+    }
+
+    builder.build()
+}
+
+fn main() {
+    let scene = torus_scene();
+    let mut world = World::new();
+    world.init_resource::<Assets<Mesh>>();
+
+    let mut total = Duration::ZERO;
+    for _ in 0..RUNS {
+        // A fresh cache every run, so that all meshes are built again
+        let elapsed = world.run_system_once(|mut meshes: ResMut<Assets<Mesh>>| {
+            let mut cache = SceneAssetCache::default();
+            let start = Instant::now();
+            prebuild_primitive_meshes(&scene, &mut meshes, &mut cache);
+            assert_eq!(cache.meshes.len(), INSTANCES);
+            start.elapsed()
+        });
+        total += elapsed;
+    }
+
+    println!(
+        "prebuild_primitive_meshes ({}): {:?} per scene of {} tori",
+        if cfg!(feature = "parallel") {
+            "parallel"
+        } else {
+            "sequential"
+        },
+        total / RUNS,
+        INSTANCES
+    );
+}
@@ -18,8 +18,10 @@ fn setup(
     mut materials: ResMut<Assets<StandardMaterial>>,
     assets: Res<AssetServer>,
 ) {
-    let world = load_scene_world_file("bevy_godot_scene_loader/examples/test-world.json");
-    load_scene_to_bevy(&world, &mut commands, &mut meshes, &mut materials, &assets);
+    let world = load_scene_world_file("bevy_godot_scene_loader/examples/test-world.json")
+        .expect("example scene should load");
+    load_scene_to_bevy(&world, &mut commands, &mut meshes, &mut materials, &assets)
+        .expect("scene should have valid transforms");
 
     commands.spawn(DirectionalLightBundle {
         directional_light: DirectionalLight {
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use bevy::ecs::component::Component;
+use common::{
+    entities::{node::ProcessMode, render::BoneData},
+    metadata::Metadata,
+};
+
+/// Metadata of the Godot node that the entity was spawned from.
+#[derive(Component, Debug, Clone, Default)]
+pub struct GodotMetadata(pub Metadata);
+
+/// Text of a Godot `Label3D` node, left for the application to render.
+#[derive(Component, Debug, Clone, Default)]
+pub struct GodotLabel(pub String);
+
+/// Bones of a Godot `Skeleton3D` node, with their rest transforms left in Godot's layout.
+#[derive(Component, Debug, Clone, Default)]
+pub struct GodotSkeleton(pub Vec<BoneData>);
+
+/// Animations of a Godot `AnimationPlayer` node, for the application's own animation system.
+#[derive(Component, Debug, Clone, Default)]
+pub struct GodotAnimations {
+    pub animations: Vec<String>,
+
+    /// Animation that Godot plays automatically when the scene is loaded.
+    pub autoplay: Option<String>,
+}
+
+/// Process mode and flags of a Godot `Node3D`, for systems that mirror Godot's pausing.
+#[derive(Component, Debug, Clone, Default)]
+pub struct GodotProcess {
+    pub mode: ProcessMode,
+    pub flags: HashMap<String, bool>,
+}
+
+/// Falloff of a Godot light, which the spawned Bevy light only approximates,
+/// see [light](crate::light).
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GodotLightAttenuation {
+    /// Godot's `omni_attenuation` or `spot_attenuation`.
+    pub attenuation: f32,
+    pub range: f32,
+
+    /// Godot's `spot_angle_attenuation`, `None` for omni lights.
+    pub angle_attenuation: Option<f32>,
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use bevy::{math::Mat4, transform::components::Transform};
+use common::{bounds::Aabb, WorldEntity};
+
+use crate::util::get_transform_from_data;
+
+type Filter = dyn Fn(&WorldEntity, &Mat4) -> bool + Send + Sync;
+
+/// Decides which entities of a scene are spawned, given the entity and its world transform.
+/// An entity that is filtered out while some of its descendants pass is spawned as a placeholder
+/// with just its name and transform, so that the hierarchy and the world transforms of the
+/// descendants stay the same. Placeholders aren't listed in the [SpawnResult](crate::SpawnResult).
+/// Entities without any passing descendants are skipped along with their whole subtree.
+///
+/// Transforms in the scene are relative to the parent, and the transform passed to the filter
+/// is already composed with those of all ancestors, whether they pass or not. The placeholders
+/// carry the same transform the full entity would have had, so filtering never moves anything.
+#[derive(Clone)]
+pub struct SpawnFilter(Arc<Filter>);
+
+impl SpawnFilter {
+    pub fn new(filter: impl Fn(&WorldEntity, &Mat4) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(filter))
+    }
+
+    /// Decides by the entity alone, e.g. its type or name.
+    ///
+    /// ```ignore
+    /// // Just the collision geometry, e.g. for a server
+    /// let filter = SpawnFilter::by_entity(|entity| entity.entity_type != "MeshInstance3D");
+    /// ```
+    pub fn by_entity(filter: impl Fn(&WorldEntity) -> bool + Send + Sync + 'static) -> Self {
+        Self::new(move |entity, _| filter(entity))
+    }
+
+    /// Only spawns entities whose origin is inside `region`, e.g. to stream a large scene
+    /// in chunks.
+    pub fn region(region: Aabb) -> Self {
+        Self::new(move |_, transform| region.contains(transform.w_axis.truncate().to_array()))
+    }
+
+    pub fn includes(&self, entity: &WorldEntity, transform: &Mat4) -> bool {
+        (self.0)(entity, transform)
+    }
+
+    /// Whether any descendant of the entity placed at `transform` passes the filter.
+    /// Descendants with a malformed transform can't be placed, so they never pass.
+    pub fn includes_descendants(&self, entity: &WorldEntity, transform: &Mat4) -> bool {
+        entity.children.iter().flatten().any(|child| {
+            let local = match get_transform_from_data(&child.data) {
+                Ok(local) => local.unwrap_or(Transform::IDENTITY),
+                Err(_) => return false,
+            };
+            let transform = *transform * local.compute_matrix();
+
+            self.includes(child, &transform) || self.includes_descendants(child, &transform)
+        })
+    }
+}
+
+impl std::fmt::Debug for SpawnFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SpawnFilter")
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bevy::ecs::{entity::Entity, system::Commands};
+use common::WorldEntity;
+
+type Hook = dyn FnMut(&WorldEntity, SpawnRef<'_, '_, '_>) + Send;
+
+/// Called right after each entity is spawned, e.g. to insert game-specific components based on
+/// the metadata. Children are spawned after their parent, so the hook sees the entities in
+/// the order of the scene tree.
+#[derive(Clone)]
+pub struct SpawnHook(Arc<Mutex<Hook>>);
+
+impl SpawnHook {
+    pub fn new(hook: impl FnMut(&WorldEntity, SpawnRef<'_, '_, '_>) + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(hook)))
+    }
+
+    pub fn call(&self, entity: &WorldEntity, spawned: SpawnRef<'_, '_, '_>) {
+        let mut hook = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        hook(entity, spawned)
+    }
+}
+
+impl std::fmt::Debug for SpawnHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SpawnHook")
+    }
+}
+
+/// The entity spawned for a [WorldEntity], passed to the [SpawnHook].
+pub struct SpawnRef<'a, 'w, 's> {
+    pub id: Entity,
+    pub commands: &'a mut Commands<'w, 's>,
+}
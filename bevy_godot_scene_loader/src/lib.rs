@@ -1,34 +1,153 @@
 use std::collections::HashMap;
 
 use bevy::{
-    asset::{AssetServer, Assets, Handle},
+    asset::{AssetServer, Assets},
+    core::Name,
     ecs::{
         entity::Entity,
         system::{Commands, Res, ResMut},
     },
-    hierarchy::BuildChildren,
-    pbr::{AlphaMode, PbrBundle, StandardMaterial},
-    prelude::{default, SpatialBundle},
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+    log::{debug, error, warn},
+    math::{Mat4, Vec3},
+    pbr::{NotShadowCaster, NotShadowReceiver, PbrBundle, StandardMaterial},
+    prelude::{default, Camera3dBundle, SpatialBundle, VisibilityBundle},
     render::{mesh::Mesh, view::Visibility},
-    scene::{Scene, SceneBundle},
+    scene::SceneBundle,
     transform::components::Transform,
 };
-pub use common::{load_scene_world_file, SceneWorld, SceneWorldJson};
-use common::{EntityData, ResourceData, WorldEntity};
-use mesh::{create_mesh_from_resource, MaterialInfo, MeshInfo};
-use physics::{kinematic_body, rigid_body, static_body};
-use util::{get_transform_from_data, strip_res_prefix};
+#[cfg(feature = "tokio")]
+pub use common::load_scene_world_file_async;
+#[cfg(feature = "zip")]
+pub use common::load_scene_world_from_archive;
+pub use common::{
+    bounds::Aabb,
+    error::LoaderError,
+    load_scene_world_file,
+    plan::{plan_scene, SpawnPlanEntry},
+    registry::ParserRegistry,
+    transform::{CoordinateSystem, TransformError},
+    try_load_scene_world_file, SceneFileError, SceneWorld, SceneWorldJson,
+};
+use common::{
+    entities::{
+        physics::{ColliderMaterial, CollisionShapeData},
+        render::ShadowCasting,
+    },
+    get_or_return_val,
+    plan::{plan_entity, SpawnPlan},
+    EntityData, WorldEntity,
+};
+use components::{GodotAnimations, GodotLabel, GodotMetadata, GodotProcess, GodotSkeleton};
+pub use filter::SpawnFilter;
+pub use hook::{SpawnHook, SpawnRef};
+pub use light::SceneEnvironment;
+use light::{omni_light, spot_light};
+pub use link::{LinkContext, Linker};
+use mesh::{prebuild_primitive_meshes, LoadingAssets, SceneAssetCache, SceneAssets};
+pub use options::{PathResolver, SceneLoadOptions};
+pub use pending::PendingAssets;
+use physics::{collision_shape, kinematic_body, rigid_body, static_body};
+pub use prepared::{instantiate, PreparedScene};
+use util::{get_transform_from_data, render_layers};
 
+pub mod components;
+pub mod filter;
+pub mod hook;
+pub mod light;
+pub mod link;
 pub mod mesh;
+pub mod options;
+pub mod pending;
 pub mod physics;
+pub mod prepared;
+pub mod primitives;
 pub mod util;
 
 pub struct SpawnedEntity {
     pub id: Entity,
     pub entity_type: String,
+
+    /// Name of the parent entity in the scene, if any.
+    pub parent: Option<String>,
+
+    /// Transform relative to the parent entity, as written in the scene.
+    pub local_transform: Transform,
+
+    /// Transform of the entity in world space at load time,
+    /// the same that Bevy will propagate into its `GlobalTransform`.
+    pub world_transform: Transform,
+}
+
+/// All entities spawned while loading a scene, keyed by their name.
+#[derive(Default)]
+pub struct SpawnResult {
+    pub entities: HashMap<String, SpawnedEntity>,
+
+    /// Names of the spawned entities grouped by their entity type.
+    types: HashMap<String, Vec<String>>,
+
+    /// Problems with entities that were spawned as empty placeholders instead, e.g. because
+    /// they reference a missing resource.
+    pub errors: Vec<LoaderError>,
+
+    /// Environment of the first `WorldEnvironment` of the scene, if any.
+    pub environment: Option<SceneEnvironment>,
+
+    /// Assets that are still loaded in the background, see [PendingAssets::is_loaded].
+    pub pending: PendingAssets,
+}
+
+impl SpawnResult {
+    pub fn insert(&mut self, name: String, entity: SpawnedEntity) {
+        self.types
+            .entry(entity.entity_type.clone())
+            .or_default()
+            .push(name.clone());
+
+        if let Some(previous) = self.entities.insert(name.clone(), entity) {
+            if let Some(names) = self.types.get_mut(&previous.entity_type) {
+                // Only drop the entry that was registered for the replaced entity
+                if let Some(index) = names.iter().position(|x| x == &name) {
+                    names.remove(index);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SpawnedEntity> {
+        self.entities.get(name)
+    }
+
+    /// Returns all spawned entities of the given Godot type, e.g. `RigidBody3D`.
+    pub fn by_type<'a>(&'a self, entity_type: &str) -> impl Iterator<Item = &'a SpawnedEntity> {
+        self.types
+            .get(entity_type)
+            .into_iter()
+            .flatten()
+            .filter_map(|name| self.entities.get(name))
+    }
+
+    /// Prepends `prefix` to the names of all entities, including the parent references.
+    pub fn prefixed(self, prefix: &str) -> SpawnResult {
+        let mut result = SpawnResult {
+            errors: self.errors,
+            environment: self.environment,
+            pending: self.pending,
+            ..Default::default()
+        };
+        for (name, mut entity) in self.entities {
+            entity.parent = entity.parent.map(|parent| format!("{}{}", prefix, parent));
+            result.insert(format!("{}{}", prefix, name), entity);
+        }
+
+        result
+    }
 }
 
 /// Loads a [SceneWorld] into Bevy by spawning all the entities in Bevy format.
+/// Fails if any entity has a malformed transform, while entities referencing missing or invalid
+/// resources are skipped and listed in [SpawnResult::errors].
 pub fn load_scene_to_bevy(
     world: &SceneWorld,
 
@@ -36,136 +155,423 @@ pub fn load_scene_to_bevy(
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     assets: &Res<AssetServer>,
-) -> HashMap<String, SpawnedEntity> {
-    let mut spawned_entities = HashMap::new();
+) -> Result<SpawnResult, TransformError> {
+    load_scene_to_bevy_with(
+        world,
+        &SceneLoadOptions::default(),
+        commands,
+        meshes,
+        materials,
+        assets,
+    )
+}
+
+/// Same as [load_scene_to_bevy], but places the whole scene with the `transform` root matrix,
+/// like the `transform` parameter of the rapier loader. Children inherit it through the hierarchy.
+pub fn load_scene_to_bevy_at(
+    world: &SceneWorld,
+    transform: Mat4,
+
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    assets: &Res<AssetServer>,
+) -> Result<SpawnResult, TransformError> {
+    let options = SceneLoadOptions {
+        root_transform: Some(transform),
+        ..Default::default()
+    };
+
+    load_scene_to_bevy_with(world, &options, commands, meshes, materials, assets)
+}
+
+/// Same as [load_scene_to_bevy], but only spawns the entities whose origin is inside `region`,
+/// along with placeholders for their ancestors, see [SpawnFilter].
+pub fn load_scene_to_bevy_filtered(
+    world: &SceneWorld,
+    region: Aabb,
+
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    assets: &Res<AssetServer>,
+) -> Result<SpawnResult, TransformError> {
+    let options = SceneLoadOptions {
+        filter: Some(SpawnFilter::region(region)),
+        ..Default::default()
+    };
+
+    load_scene_to_bevy_with(world, &options, commands, meshes, materials, assets)
+}
+
+/// Same as [load_scene_to_bevy], but prepends `prefix` to the names in the returned [SpawnResult]
+/// so that several scenes sharing entity names can be loaded side by side.
+pub fn load_scene_to_bevy_with_prefix(
+    world: &SceneWorld,
+    prefix: &str,
+
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    assets: &Res<AssetServer>,
+) -> Result<SpawnResult, TransformError> {
+    let options = SceneLoadOptions {
+        prefix: prefix.to_owned(),
+        ..Default::default()
+    };
+
+    load_scene_to_bevy_with(world, &options, commands, meshes, materials, assets)
+}
+
+/// Loads a [SceneWorld] into Bevy according to the given [SceneLoadOptions].
+pub fn load_scene_to_bevy_with(
+    world: &SceneWorld,
+    options: &SceneLoadOptions,
+
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    assets: &Res<AssetServer>,
+) -> Result<SpawnResult, TransformError> {
+    let mut cache = SceneAssetCache::default();
+    prebuild_primitive_meshes(world, meshes, &mut cache);
+
+    let mut assets = LoadingAssets {
+        meshes,
+        materials,
+        asset_server: assets,
+        paths: &options.path_resolver,
+        color_space: options.color_space,
+        cache: &mut cache,
+        pending: PendingAssets::default(),
+    };
+
+    spawn_world(world, options, commands, &mut assets)
+}
+
+/// Spawns all entities of `world`, taking the handles of their assets from `assets`.
+pub(crate) fn spawn_world(
+    world: &SceneWorld,
+    options: &SceneLoadOptions,
+    commands: &mut Commands,
+    assets: &mut impl SceneAssets,
+) -> Result<SpawnResult, TransformError> {
+    let mut spawned_entities = SpawnResult::default();
+    for err in &world.errors {
+        warn!("skipping {}", err);
+    }
+    spawned_entities.errors.extend(world.errors.iter().cloned());
+
+    let basis = Mat4::from_cols_array(&options.coordinate_system.to_y_up_matrix());
+    let scale = Mat4::from_scale(Vec3::splat(options.scene_scale));
+    let root = options.root_transform.unwrap_or(Mat4::IDENTITY) * basis * scale;
     for entity in &world.entities {
         spawn_entity(
             world,
             entity,
+            None,
+            root,
+            options,
             commands,
-            meshes,
-            materials,
             assets,
             &mut spawned_entities,
-        );
+        )?;
+    }
+
+    // Second phase, resolving references by name now that all entities are spawned
+    let mut errors = vec![];
+    let mut context = LinkContext {
+        commands,
+        spawned: &spawned_entities,
+    };
+    for linker in &options.linkers {
+        for (entity, _, _) in world.iter_entities() {
+            if let Err(err) = linker.link(entity, &mut context) {
+                warn!("unable to link {}: {}", entity.name, err);
+                errors.push(err);
+            }
+        }
+    }
+    spawned_entities.errors.extend(errors);
+
+    spawned_entities.pending = assets.pending();
+
+    // Godot only uses one environment at a time
+    let environment = world
+        .iter_entities()
+        .find_map(|(entity, _, _)| match &entity.data {
+            EntityData::WorldEnvironment(environment) => Some(environment),
+            _ => None,
+        });
+    spawned_entities.environment = environment.map(SceneEnvironment::from_data);
+    if options.apply_environment {
+        if let Some(environment) = &spawned_entities.environment {
+            environment.apply(commands);
+        }
+    }
+
+    if !options.prefix.is_empty() {
+        return Ok(spawned_entities.prefixed(&options.prefix));
     }
 
-    return spawned_entities;
+    return Ok(spawned_entities);
+}
+
+/// Despawns all entities of a scene previously loaded with [load_scene_to_bevy].
+/// Mesh and material handles are dropped along with the entities, so assets that aren't
+/// used elsewhere get unloaded by Bevy.
+pub fn unload_scene(commands: &mut Commands, spawned: &SpawnResult) {
+    for entity in spawned.entities.values() {
+        // Children are despawned recursively along with their root
+        let is_root = match &entity.parent {
+            Some(parent) => !spawned.entities.contains_key(parent),
+            None => true,
+        };
+
+        if is_root {
+            if let Some(entity_commands) = commands.get_entity(entity.id) {
+                entity_commands.despawn_recursive();
+            }
+        }
+    }
 }
 
 /// Spawns a [WorldEntity] from [SceneWorld] into the Bevy scene.
+/// `parent_transform` is the world transform of the parent, or the root transform of the scene
+/// for top-level entities, in which case it's pre-multiplied onto the transform of the entity.
+/// Returns `None` if the entity and all of its descendants are left out by the filter
+/// of the options.
 pub fn spawn_entity(
     world: &SceneWorld,
     entity: &WorldEntity,
+    parent: Option<&WorldEntity>,
+    parent_transform: Mat4,
+    options: &SceneLoadOptions,
     commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    assets: &Res<AssetServer>,
-    mut spawned_entities: &mut HashMap<String, SpawnedEntity>,
-) -> Option<Entity> {
-    let relative_transform = get_transform_from_data(&entity.data).unwrap_or(Transform::IDENTITY);
+    assets: &mut impl SceneAssets,
+    mut spawned_entities: &mut SpawnResult,
+) -> Result<Option<Entity>, TransformError> {
+    let mut relative_transform = get_transform_from_data(&entity.data)
+        .map_err(|err| {
+            let err = err.for_entity(&entity.name);
+            error!("{}", err);
+            err
+        })?
+        .unwrap_or(Transform::IDENTITY);
+    let local_transform = relative_transform;
 
-    // Spawn the components for this entity
-    let entity_id = if let Some(id) = spawn_components(
-        world,
-        entity,
-        relative_transform,
-        commands,
-        meshes,
-        materials,
-        assets,
-    ) {
+    let absolute_transform = parent_transform * relative_transform.compute_matrix();
+    if parent.is_none() {
+        // Top-level entities have no Bevy parent to inherit the root transform from
+        relative_transform = Transform::from_matrix(absolute_transform);
+    }
+
+    // Filtered out entities are only kept as placeholders for their descendants
+    let included = match &options.filter {
+        Some(filter) if !filter.includes(entity, &absolute_transform) => {
+            if !filter.includes_descendants(entity, &absolute_transform) {
+                return Ok(None);
+            }
+            false
+        }
+        _ => true,
+    };
+
+    // Spawn the components for this entity, or a placeholder if it's not supported or invalid
+    // so that its children still end up in the right place.
+    let components = if included {
+        spawn_components(
+            world,
+            entity,
+            parent,
+            relative_transform,
+            options,
+            commands,
+            assets,
+        )
+        .unwrap_or_else(|err| {
+            warn!("skipping {}: {}", entity.name, err);
+            spawned_entities.errors.push(err);
+            None
+        })
+    } else {
+        None
+    };
+
+    let entity_id = if let Some(id) = components {
         id
     } else {
-        return None;
+        commands
+            .spawn(SpatialBundle::default())
+            .insert(relative_transform)
+            .id()
     };
 
-    spawned_entities.insert(
-        entity.name.clone(),
-        SpawnedEntity {
-            id: entity_id,
-            entity_type: entity.entity_type.clone(),
-        },
-    );
+    commands
+        .entity(entity_id)
+        .insert(Name::new(entity.name.clone()));
+
+    // Bevy propagates the visibility to the children, like Godot does
+    if entity.data.visible() == Some(false) {
+        commands.entity(entity_id).insert(VisibilityBundle {
+            visibility: Visibility::Hidden,
+            ..default()
+        });
+    }
+
+    if included {
+        debug!("spawned {} ({})", entity.name, entity.entity_type);
+
+        if options.insert_metadata {
+            commands
+                .entity(entity_id)
+                .insert(GodotMetadata(entity.metadata.clone()));
+        }
+
+        spawned_entities.insert(
+            entity.name.clone(),
+            SpawnedEntity {
+                id: entity_id,
+                entity_type: entity.entity_type.clone(),
+                parent: parent.map(|x| x.name.clone()),
+                local_transform,
+                world_transform: Transform::from_matrix(absolute_transform),
+            },
+        );
+
+        if let Some(hook) = &options.on_spawn {
+            let spawned = SpawnRef {
+                id: entity_id,
+                commands,
+            };
+            hook.call(entity, spawned);
+        }
+    }
 
     // Spawn the children of this entity and add them as Bevy children.
     if let Some(children) = &entity.children {
         for child in children {
-            if let Some(child_id) = spawn_entity(
+            let child_id = spawn_entity(
                 world,
                 &child,
+                Some(entity),
+                absolute_transform,
+                options,
                 commands,
-                meshes,
-                materials,
                 assets,
                 &mut spawned_entities,
-            ) {
+            )?;
+
+            if let Some(child_id) = child_id {
                 commands.entity(entity_id).add_child(child_id);
             }
         }
     }
 
-    Some(entity_id)
+    Ok(Some(entity_id))
 }
 
 /// Spawns the components that this entity corresponds to in Bevy format.
-/// Returns the spawned entity ID if it was successful.
+/// Returns the spawned entity ID, or `None` if the entity type isn't supported.
 pub fn spawn_components(
     world: &SceneWorld,
     entity: &WorldEntity,
+    parent: Option<&WorldEntity>,
     transform: Transform,
+    options: &SceneLoadOptions,
     mut commands: &mut Commands,
+    assets: &mut impl SceneAssets,
+) -> Result<Option<Entity>, LoaderError> {
+    // Shapes of an area are always sensors
+    let in_area = matches!(parent.map(|x| &x.data), Some(EntityData::Area3D(_)));
 
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    assets: &Res<AssetServer>,
-) -> Option<Entity> {
-    match &entity.data {
-        EntityData::StaticBody3D(_) => {
-            let entity = static_body(&mut commands);
+    Ok(match plan_entity(entity, in_area, &world.resources)? {
+        SpawnPlan::StaticBody => {
+            let entity = static_body(&mut commands, &entity.metadata);
             Some(commands.entity(entity).insert(transform).id())
         }
 
-        EntityData::RigidBody3D(_) => {
-            let entity = rigid_body(&mut commands);
+        SpawnPlan::RigidBody(body) => {
+            let entity = rigid_body(&mut commands, body, &entity.metadata);
             Some(commands.entity(entity).insert(transform).id())
         }
 
-        EntityData::KinematicBody3D(_) => {
-            let entity = kinematic_body(&mut commands);
+        SpawnPlan::KinematicBody(_) => {
+            let entity = kinematic_body(&mut commands, &entity.metadata);
 
             Some(commands.entity(entity).insert(transform).id())
         }
-        EntityData::Node3D(_) => Some(
+        SpawnPlan::Node(node) => {
+            let mut entity = commands.spawn(SpatialBundle::default());
+            entity.insert(transform);
+
+            if node.process_mode.is_some() || node.process_flags.is_some() {
+                entity.insert(GodotProcess {
+                    mode: node.process_mode.unwrap_or_default(),
+                    flags: node.process_flags.clone().unwrap_or_default(),
+                });
+            }
+
+            Some(entity.id())
+        }
+        SpawnPlan::Camera if options.spawn_cameras => Some(
+            commands
+                .spawn(Camera3dBundle {
+                    transform,
+                    ..default()
+                })
+                .id(),
+        ),
+        SpawnPlan::OmniLight(light) if options.spawn_lights => {
+            Some(omni_light(commands, light, transform))
+        }
+        SpawnPlan::SpotLight(light) if options.spawn_lights => {
+            Some(spot_light(commands, light, transform))
+        }
+        SpawnPlan::Area | SpawnPlan::Joint | SpawnPlan::Unknown => Some(
             commands
                 .spawn(SpatialBundle::default())
                 .insert(transform)
                 .id(),
         ),
-        EntityData::CollisionShape3D(_) => Some(
+        SpawnPlan::Label(label) => Some(
             commands
                 .spawn(SpatialBundle::default())
                 .insert(transform)
+                .insert(GodotLabel(label.text.clone()))
                 .id(),
         ),
-        EntityData::MeshInstance3D(instance) => {
-            let mesh = create_mesh_from_resource(instance.mesh.clone(), &world.resources, &assets);
-
-            // Create the material for this mesh
-            let material = match mesh.material {
-                MaterialInfo::Texture(tex) => materials.add(StandardMaterial {
-                    base_color_texture: Some(tex.clone()),
-                    alpha_mode: AlphaMode::Blend,
-                    ..default()
-                }),
-                MaterialInfo::Material(mat) => materials.add(mat),
-            };
-
-            // Create the actual mesh
-            let handle = match mesh.mesh {
-                MeshInfo::Mesh(mh) => meshes.add(mh),
-                MeshInfo::ArrayMesh(am) => am,
-            };
+        SpawnPlan::Skeleton(skeleton) => Some(
+            commands
+                .spawn(SpatialBundle::default())
+                .insert(transform)
+                .insert(GodotSkeleton(skeleton.bones.clone()))
+                .id(),
+        ),
+        SpawnPlan::AnimationPlayer(player) => Some(
+            commands
+                .spawn(SpatialBundle::default())
+                .insert(transform)
+                .insert(GodotAnimations {
+                    animations: player.animations.clone(),
+                    autoplay: player.autoplay.clone(),
+                })
+                .id(),
+        ),
+        SpawnPlan::Collider {
+            shape,
+            resource,
+            sensor,
+        } => Some(collision_shape(
+            &mut commands,
+            shape,
+            resource,
+            sensor,
+            ColliderMaterial::from_metadata(&entity.metadata),
+            transform,
+        )?),
+        SpawnPlan::Mesh { instance, resource } => {
+            let (handle, material) = assets.mesh(&instance.mesh, resource, &world.resources)?;
 
             // Component for if this mesh should be visible or not
             let visibility = if instance.visible {
@@ -174,46 +580,125 @@ pub fn spawn_components(
                 Visibility::Hidden
             };
 
+            let mut mesh = commands.spawn(PbrBundle {
+                mesh: handle,
+                material,
+                ..default()
+            });
+            mesh.insert(visibility).insert(transform);
+
+            if let Some(layers) = instance.visibility_layer.and_then(render_layers) {
+                mesh.insert(layers);
+            }
+
+            match instance.cast_shadow.unwrap_or_default() {
+                ShadowCasting::Off => {
+                    mesh.insert(NotShadowCaster);
+                }
+                // Bevy can't draw just the shadow of a mesh, so it's kept visible but doesn't
+                // receive shadows itself
+                ShadowCasting::ShadowsOnly => {
+                    mesh.insert(NotShadowReceiver);
+                }
+                ShadowCasting::On | ShadowCasting::DoubleSided => {}
+            }
+
+            Some(mesh.id())
+        }
+        SpawnPlan::GridMap(grid) => {
+            let grid_entity = commands
+                .spawn(SpatialBundle::default())
+                .insert(transform)
+                .id();
+
+            // Every cell gets its own entity, sharing the assets of its item
+            for (cell, name) in grid.items() {
+                let resource = &world.resources[name].data;
+                let cell_transform =
+                    Transform::from_matrix(Mat4::from_cols_array(&grid.cell_transform(cell)));
+
+                let cell_entity = if resource.is_collision_shape() {
+                    let shape = CollisionShapeData {
+                        shape: name.to_owned(),
+                        transform: vec![],
+                        disabled: None,
+                    };
+                    let material = ColliderMaterial::from_metadata(&entity.metadata);
+                    collision_shape(
+                        &mut commands,
+                        &shape,
+                        resource,
+                        false,
+                        material,
+                        cell_transform,
+                    )?
+                } else {
+                    let (mesh, material) = assets.mesh(name, resource, &world.resources)?;
+                    commands
+                        .spawn(PbrBundle {
+                            mesh,
+                            material,
+                            transform: cell_transform,
+                            ..default()
+                        })
+                        .id()
+                };
+
+                commands.entity(grid_entity).add_child(cell_entity);
+            }
+
+            Some(grid_entity)
+        }
+        SpawnPlan::Scene(scene) => {
+            let path = options.path_resolver.resolve(&scene.path);
+            let path =
+                get_or_return_val!(scene_asset_path(path, scene.scene_index, options), Ok(None));
+            let scene_handle = get_or_return_val!(assets.scene(path), Ok(None));
+
             Some(
                 commands
-                    .spawn(PbrBundle {
-                        mesh: handle,
-                        material,
-                        ..default()
+                    .spawn(SceneBundle {
+                        scene: scene_handle,
+                        transform,
+                        ..Default::default()
                     })
-                    .insert(visibility)
-                    .insert(transform)
                     .id(),
             )
         }
-        EntityData::ModelScene(scene) => match scene.type_name.as_str() {
-            "MODEL" => {
-                if let Some(path) = scene.data.as_str() {
-                    // Get resource from path
-                    if let Some(resource) = world.resources.get(path) {
-                        // Resource must be of type PackedScene
-                        if let ResourceData::PackedScene(scene) = &resource.data {
-                            let mut path = strip_res_prefix(&scene.path);
-                            path = format!("{}#Scene0", path); // Use the first scene
-
-                            let scene_handle: Handle<Scene> = assets.load(path);
-                            return Some(
-                                commands
-                                    .spawn(SceneBundle {
-                                        scene: scene_handle,
-                                        transform,
-                                        ..Default::default()
-                                    })
-                                    .id(),
-                            );
-                        }
-                    }
-                }
+        SpawnPlan::Camera
+        | SpawnPlan::OmniLight(_)
+        | SpawnPlan::SpotLight(_)
+        | SpawnPlan::Environment(_)
+        | SpawnPlan::Placeholder => None,
+    })
+}
 
-                None
-            }
-            _ => None,
-        },
-        _ => None,
+/// Asset path of a packed scene, selecting the scene at `scene_index` of glTF files,
+/// or the configured scene if the packed scene doesn't specify one.
+/// Returns `None` for Godot's own scene formats, which Bevy can't load.
+pub(crate) fn scene_asset_path(
+    path: String,
+    scene_index: Option<usize>,
+    options: &SceneLoadOptions,
+) -> Option<String> {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "glb" | "gltf" => Some(match scene_index {
+            Some(index) => format!("{}#Scene{}", path, index),
+            None => format!("{}#{}", path, options.scene_label),
+        }),
+        "tscn" | "scn" => {
+            warn!(
+                "unable to load Godot scene {}, map it to a glTF file with a path resolver",
+                path
+            );
+            None
+        }
+        // Possibly handled by a custom asset loader
+        _ => Some(path),
     }
 }
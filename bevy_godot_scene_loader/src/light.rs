@@ -0,0 +1,151 @@
+//! Conversion of Godot lights to Bevy lights.
+//!
+//! Both engines fade a light out towards its range the same way, but Bevy lights always fall
+//! off with the inverse square of the distance, while Godot's falloff is `distance^-attenuation`
+//! with an attenuation of 1 by default. The range is kept as is, and the intensity is scaled so
+//! that both falloffs match at half of the range, see [attenuation_scale]. Lights with
+//! an attenuation of 2 convert exactly, others are brighter than in Godot close to the light
+//! and darker further away when the attenuation is below 2, and the other way around above it.
+//!
+//! A Godot energy of 1 is mapped to the default intensity of Bevy lights. The raw values are
+//! kept on a [GodotLightAttenuation] component, for applications that want to do better.
+//! The ambient light of a `WorldEnvironment` is scaled the same way, see [SceneEnvironment].
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    ecs::{entity::Entity, system::Commands},
+    pbr::{AmbientLight, PointLight, PointLightBundle, SpotLight, SpotLightBundle},
+    render::{camera::ClearColor, color::Color},
+    transform::components::Transform,
+};
+use common::entities::light::{OmniLightData, SpotLightData, WorldEnvironmentData};
+
+use crate::components::GodotLightAttenuation;
+
+/// Godot's default `omni_range` and `spot_range`.
+const DEFAULT_RANGE: f32 = 5.0;
+
+/// Godot's default `spot_angle`, in degrees.
+const DEFAULT_SPOT_ANGLE: f32 = 45.0;
+
+/// Factor applied to the intensity of a Bevy light, so that its inverse square falloff matches
+/// the falloff of a Godot light with `attenuation` at half of `range`.
+pub fn attenuation_scale(range: f32, attenuation: f32) -> f32 {
+    (range * 0.5).max(f32::EPSILON).powf(2.0 - attenuation)
+}
+
+/// Inner angle of a Bevy spot light, approximating Godot's `spot_angle_attenuation`.
+/// Godot fades the light linearly from the center to the edge of the cone with an exponent of 1,
+/// like Bevy does without an inner angle. Larger exponents keep the cone bright further out,
+/// so the inner angle is moved towards the outer one.
+pub fn spot_inner_angle(outer_angle: f32, angle_attenuation: f32) -> f32 {
+    outer_angle * (1.0 - 1.0 / angle_attenuation.max(1.0))
+}
+
+fn to_color(color: &Option<Vec<f32>>) -> Option<Color> {
+    match color.as_deref()? {
+        [r, g, b, a, ..] => Some(Color::rgba(*r, *g, *b, *a)),
+        [r, g, b] => Some(Color::rgb(*r, *g, *b)),
+        _ => None,
+    }
+}
+
+pub fn omni_light(commands: &mut Commands, light: &OmniLightData, transform: Transform) -> Entity {
+    let range = light.range.unwrap_or(DEFAULT_RANGE);
+    let attenuation = light.attenuation.unwrap_or(1.0);
+    let energy = light.energy.unwrap_or(1.0);
+
+    commands
+        .spawn(PointLightBundle {
+            point_light: PointLight {
+                color: to_color(&light.color).unwrap_or(Color::WHITE),
+                intensity: PointLight::default().intensity
+                    * energy
+                    * attenuation_scale(range, attenuation),
+                range,
+                shadows_enabled: light.shadow_enabled.unwrap_or(false),
+                ..PointLight::default()
+            },
+            transform,
+            ..PointLightBundle::default()
+        })
+        .insert(GodotLightAttenuation {
+            attenuation,
+            range,
+            angle_attenuation: None,
+        })
+        .id()
+}
+
+pub fn spot_light(commands: &mut Commands, light: &SpotLightData, transform: Transform) -> Entity {
+    let range = light.range.unwrap_or(DEFAULT_RANGE);
+    let attenuation = light.attenuation.unwrap_or(1.0);
+    let angle_attenuation = light.angle_attenuation.unwrap_or(1.0);
+    let energy = light.energy.unwrap_or(1.0);
+
+    // Bevy doesn't support cones wider than a hemisphere
+    let outer_angle = light
+        .angle
+        .unwrap_or(DEFAULT_SPOT_ANGLE)
+        .to_radians()
+        .min(FRAC_PI_2);
+
+    commands
+        .spawn(SpotLightBundle {
+            spot_light: SpotLight {
+                color: to_color(&light.color).unwrap_or(Color::WHITE),
+                intensity: SpotLight::default().intensity
+                    * energy
+                    * attenuation_scale(range, attenuation),
+                range,
+                shadows_enabled: light.shadow_enabled.unwrap_or(false),
+                outer_angle,
+                inner_angle: spot_inner_angle(outer_angle, angle_attenuation),
+                ..SpotLight::default()
+            },
+            transform,
+            ..SpotLightBundle::default()
+        })
+        .insert(GodotLightAttenuation {
+            attenuation,
+            range,
+            angle_attenuation: Some(angle_attenuation),
+        })
+        .id()
+}
+
+/// Global resources of a Godot `WorldEnvironment`, see
+/// [SceneLoadOptions::apply_environment](crate::options::SceneLoadOptions::apply_environment).
+#[derive(Debug, Clone, Default)]
+pub struct SceneEnvironment {
+    /// Set when the environment has a custom ambient light.
+    pub ambient_light: Option<AmbientLight>,
+
+    /// Set when the background of the environment is a plain color.
+    pub clear_color: Option<ClearColor>,
+}
+
+impl SceneEnvironment {
+    pub fn from_data(environment: &WorldEnvironmentData) -> Self {
+        let energy = environment.ambient_energy.unwrap_or(1.0);
+
+        Self {
+            ambient_light: to_color(&environment.ambient_color).map(|color| AmbientLight {
+                color,
+                brightness: AmbientLight::default().brightness * energy,
+            }),
+            clear_color: to_color(&environment.background_color).map(ClearColor),
+        }
+    }
+
+    /// Inserts the resources, replacing the ones of previously loaded scenes.
+    pub fn apply(&self, commands: &mut Commands) {
+        if let Some(ambient_light) = &self.ambient_light {
+            commands.insert_resource(ambient_light.clone());
+        }
+        if let Some(clear_color) = &self.clear_color {
+            commands.insert_resource(clear_color.clone());
+        }
+    }
+}
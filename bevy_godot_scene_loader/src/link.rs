@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use bevy::ecs::system::Commands;
+use common::{error::LoaderError, WorldEntity};
+
+use crate::SpawnResult;
+
+type Link =
+    dyn Fn(&WorldEntity, &mut LinkContext<'_, '_, '_>) -> Result<(), LoaderError> + Send + Sync;
+
+/// Resolves references between entities by name, e.g. the target of a follower. Linkers run
+/// once all entities are spawned, so that references can point anywhere in the tree. Every
+/// linker is called with every entity, in the order of [SceneLoadOptions::linkers].
+///
+/// [SceneLoadOptions::linkers]: crate::SceneLoadOptions::linkers
+#[derive(Clone)]
+pub struct Linker(Arc<Link>);
+
+impl Linker {
+    pub fn new(
+        link: impl Fn(&WorldEntity, &mut LinkContext<'_, '_, '_>) -> Result<(), LoaderError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self(Arc::new(link))
+    }
+
+    pub fn link(
+        &self,
+        entity: &WorldEntity,
+        context: &mut LinkContext<'_, '_, '_>,
+    ) -> Result<(), LoaderError> {
+        (self.0)(entity, context)
+    }
+}
+
+impl std::fmt::Debug for Linker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Linker")
+    }
+}
+
+/// What the linkers can look up and modify.
+pub struct LinkContext<'a, 'w, 's> {
+    pub commands: &'a mut Commands<'w, 's>,
+
+    /// All spawned entities by their name in the scene, without the prefix of the options.
+    pub spawned: &'a SpawnResult,
+}
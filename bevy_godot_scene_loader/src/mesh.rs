@@ -1,106 +1,570 @@
 use std::collections::HashMap;
 
 use bevy::{
-    asset::{AssetServer, Handle},
-    ecs::system::Res,
-    math::primitives::{Cuboid, Sphere},
-    pbr::StandardMaterial,
-    render::{color::Color, mesh::Mesh, texture::Image},
+    asset::{AssetServer, Assets, Handle},
+    ecs::system::{Res, ResMut},
+    math::{
+        primitives::{Cuboid, Sphere},
+        Vec3,
+    },
+    pbr::{AlphaMode, StandardMaterial},
+    render::{
+        color::Color,
+        mesh::{Mesh, Meshable},
+        render_resource::Face,
+        texture::{Image, ImageLoaderSettings},
+    },
+    scene::Scene,
+};
+use common::{
+    error::LoaderError,
+    resources::render::{
+        ColorSpace, CullMode, MeshMaterial, ORMMaterialData, ShaderMaterialData,
+        StandardMaterialData, Transparency,
+    },
+    EntityData, ResourceData, SceneWorld, WorldResource,
 };
-use common::{resources::render::StandardMaterialData, ResourceData, WorldResource};
 
-use crate::util::strip_res_prefix;
+use crate::{
+    options::PathResolver,
+    pending::PendingAssets,
+    primitives::{prism_mesh, subdivided_box_mesh, torus_mesh},
+};
 
 pub enum MeshInfo {
     ArrayMesh(Handle<Mesh>),
     Mesh(Mesh),
+
+    /// Primitive mesh that was already added to the assets, see [SceneAssetCache].
+    Cached(Handle<Mesh>),
+}
+
+/// Texture handles referenced by a material.
+#[derive(Default)]
+pub struct MaterialTextures {
+    pub albedo: Option<Handle<Image>>,
+    pub normal: Option<Handle<Image>>,
+    pub metallic_roughness: Option<Handle<Image>>,
+    pub occlusion: Option<Handle<Image>>,
+}
+
+impl MaterialTextures {
+    pub fn handles(&self) -> impl Iterator<Item = &Handle<Image>> {
+        [
+            &self.albedo,
+            &self.normal,
+            &self.metallic_roughness,
+            &self.occlusion,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Everything needed to build the Bevy material of a mesh.
+/// Absent values fall back to the [StandardMaterial] defaults.
+#[derive(Default)]
+pub struct MaterialInfo {
+    pub base_color: Option<Color>,
+    pub textures: MaterialTextures,
+
+    pub metallic: Option<f32>,
+    pub roughness: Option<f32>,
+    pub emissive: Option<Color>,
+    pub alpha_mode: AlphaMode,
+    pub cull_mode: CullMode,
+}
+
+impl MaterialInfo {
+    /// Resolves a Godot material, loading all of its referenced textures.
+    /// Its colors are read in `color_space`, unless the material specifies its own.
+    pub fn from_data(
+        material: &StandardMaterialData,
+        resources: &HashMap<String, WorldResource>,
+        asset_server: &Res<AssetServer>,
+        paths: &PathResolver,
+        color_space: ColorSpace,
+    ) -> Self {
+        let color_space = material.color_space.unwrap_or(color_space);
+
+        let textures = MaterialTextures {
+            albedo: load_texture(
+                material.albedo_texture.as_ref(),
+                false,
+                resources,
+                asset_server,
+                paths,
+            ),
+            normal: load_texture(
+                material.normal_texture.as_ref(),
+                true,
+                resources,
+                asset_server,
+                paths,
+            ),
+            // Bevy samples both metallic and roughness from one texture, so prefer the metallic one
+            // and fall back to the roughness texture (they are usually the same packed image).
+            metallic_roughness: load_texture(
+                material
+                    .metallic_texture
+                    .as_ref()
+                    .or(material.roughness_texture.as_ref()),
+                true,
+                resources,
+                asset_server,
+                paths,
+            ),
+        };
+
+        // The energy scales the emitted light, so it's applied in linear space
+        let emissive = material.emission.as_ref().map(|emission| {
+            let energy = material.emission_energy.unwrap_or(1.0);
+            let [r, g, b, _] = material_color(emission, color_space).as_linear_rgba_f32();
+            Color::rgb_linear(r * energy, g * energy, b * energy)
+        });
+
+        let alpha_mode = match material.transparency.unwrap_or_default() {
+            Transparency::Disabled => AlphaMode::Opaque,
+            Transparency::Alpha | Transparency::AlphaDepthPrePass => AlphaMode::Blend,
+            // Bevy has no hashed transparency, so approximate it with a cutoff
+            Transparency::AlphaScissor | Transparency::AlphaHash => {
+                AlphaMode::Mask(material.alpha_scissor_threshold.unwrap_or(0.5))
+            }
+        };
+
+        Self {
+            base_color: Some(material_color(&material.albedo_color, color_space)),
+            textures,
+            metallic: material.metallic,
+            roughness: material.roughness,
+            emissive,
+            alpha_mode,
+            cull_mode: material.cull_mode.unwrap_or_default(),
+        }
+    }
+
+    /// Resolves a Godot ORM material. Godot packs occlusion, roughness and metallic into the
+    /// R, G and B channels, which happens to be the glTF layout that Bevy samples both
+    /// `metallic_roughness_texture` (G and B) and `occlusion_texture` (R) from,
+    /// so the same texture is used for both. Textures packed differently won't look right.
+    pub fn from_orm_data(
+        material: &ORMMaterialData,
+        resources: &HashMap<String, WorldResource>,
+        asset_server: &Res<AssetServer>,
+        paths: &PathResolver,
+        color_space: ColorSpace,
+    ) -> Self {
+        let mut info = Self::from_data(
+            &material.material,
+            resources,
+            asset_server,
+            paths,
+            color_space,
+        );
+
+        let orm = load_texture(
+            material.orm_texture.as_ref(),
+            true,
+            resources,
+            asset_server,
+            paths,
+        );
+        if orm.is_some() {
+            info.textures.metallic_roughness = orm.clone();
+            info.textures.occlusion = orm;
+        }
+
+        info
+    }
+
+    /// Solid color stand-in for a Godot shader material, read in `color_space`.
+    pub fn from_shader_data(material: &ShaderMaterialData, color_space: ColorSpace) -> Self {
+        let base_color = material
+            .albedo_color
+            .as_ref()
+            .map(|color| material_color(color, color_space));
+
+        // There is no transparency mode to go by, so only blend translucent colors
+        let alpha_mode = match base_color {
+            Some(color) if color.a() < 1.0 => AlphaMode::Blend,
+            _ => AlphaMode::Opaque,
+        };
+
+        Self {
+            base_color,
+            alpha_mode,
+            ..Default::default()
+        }
+    }
+
+    /// Builds the complete Bevy material.
+    pub fn to_standard_material(&self) -> StandardMaterial {
+        let defaults = StandardMaterial::default();
+        let base_color = self.base_color.unwrap_or(defaults.base_color);
+
+        // Back faces that end up visible need their normals flipped to be lit correctly
+        let (cull_mode, double_sided) = match self.cull_mode {
+            CullMode::Back => (Some(Face::Back), false),
+            CullMode::Front => (Some(Face::Front), true),
+            CullMode::Disabled => (None, true),
+        };
+
+        StandardMaterial {
+            base_color,
+            base_color_texture: self.textures.albedo.clone(),
+            normal_map_texture: self.textures.normal.clone(),
+            metallic_roughness_texture: self.textures.metallic_roughness.clone(),
+            occlusion_texture: self.textures.occlusion.clone(),
+            metallic: self.metallic.unwrap_or(defaults.metallic),
+            perceptual_roughness: self.roughness.unwrap_or(defaults.perceptual_roughness),
+            emissive: self.emissive.unwrap_or(defaults.emissive),
+            alpha_mode: self.alpha_mode,
+            cull_mode,
+            double_sided,
+            ..defaults
+        }
+    }
 }
 
-pub enum MaterialInfo {
-    Texture(Handle<Image>),
-    Material(StandardMaterial),
+/// Bevy color of a serialized RGB or RGBA material color. Other lengths are rejected by
+/// [check_mesh](common::plan::check_mesh), and fall back to white.
+pub fn material_color(color: &[f32], color_space: ColorSpace) -> Color {
+    let [r, g, b, a] = match *color {
+        [r, g, b] => [r, g, b, 1.0],
+        [r, g, b, a] => [r, g, b, a],
+        _ => [1.0; 4],
+    };
+
+    match color_space {
+        ColorSpace::Srgb => Color::rgba(r, g, b, a),
+        ColorSpace::Linear => Color::rgba_linear(r, g, b, a),
+    }
 }
 
 pub struct MeshData {
     pub mesh: MeshInfo,
     pub material: MaterialInfo,
+
+    /// Name of the material resource, or of the mesh for inline materials.
+    /// Used as the key of the material in the [SceneAssetCache].
+    pub material_name: Option<String>,
+}
+
+/// Handles of assets that were already created while loading a scene,
+/// so that resources shared between entities map to a single Bevy asset.
+#[derive(Default)]
+pub struct SceneAssetCache {
+    pub materials: HashMap<String, Handle<StandardMaterial>>,
+
+    /// Primitive meshes keyed by their mesh resource name.
+    /// Array meshes are already shared by path through the asset server.
+    pub meshes: HashMap<String, Handle<Mesh>>,
+}
+
+/// Generates the geometry of a primitive mesh resource, which is pure CPU work.
+/// Returns `None` for resources that aren't primitive meshes.
+pub fn create_primitive_mesh(resource: &ResourceData) -> Option<Mesh> {
+    Some(match resource {
+        ResourceData::BoxMesh(bm) => {
+            let subdivisions = [
+                bm.subdivide_width.unwrap_or(0),
+                bm.subdivide_height.unwrap_or(0),
+                bm.subdivide_depth.unwrap_or(0),
+            ];
+            let size = Vec3::from(bm.size);
+
+            if subdivisions == [0, 0, 0] {
+                Cuboid::from_size(size).into()
+            } else {
+                subdivided_box_mesh(size, subdivisions)
+            }
+        }
+        ResourceData::SphereMesh(sm) => {
+            let sphere = Sphere { radius: sm.radius };
+            match (sm.radial_segments, sm.rings) {
+                (None, None) => sphere.into(),
+                // Same defaults as Godot when only one of them was exported
+                (radial_segments, rings) => sphere
+                    .mesh()
+                    .uv(radial_segments.unwrap_or(64), rings.unwrap_or(32)),
+            }
+        }
+        ResourceData::PrismMesh(pm) => {
+            let size = Vec3::from(pm.size);
+            prism_mesh(size, pm.left_to_right.unwrap_or(0.5))
+        }
+        ResourceData::TorusMesh(tm) => torus_mesh(
+            tm.inner_radius,
+            tm.outer_radius,
+            tm.rings.unwrap_or(64),
+            tm.ring_segments.unwrap_or(32),
+        ),
+        _ => return None,
+    })
+}
+
+/// Builds the primitive meshes referenced by all mesh instances of the world up front and adds
+/// them to the cache, so that spawning only has to look them up. With the `parallel` feature
+/// the meshes are generated on the rayon thread pool.
+pub fn prebuild_primitive_meshes(
+    world: &SceneWorld,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    cache: &mut SceneAssetCache,
+) {
+    let mut names: Vec<&String> = world
+        .iter_entities()
+        .filter_map(|(entity, _, _)| match &entity.data {
+            EntityData::MeshInstance3D(instance) => Some(&instance.mesh),
+            _ => None,
+        })
+        .filter(|name| !cache.meshes.contains_key(*name))
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let build = |name: &&String| {
+        let resource = world.resources.get(*name)?;
+        create_primitive_mesh(&resource.data).map(|mesh| ((*name).clone(), mesh))
+    };
+
+    #[cfg(feature = "parallel")]
+    let built: Vec<(String, Mesh)> = {
+        use rayon::prelude::*;
+        names.par_iter().filter_map(build).collect()
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let built: Vec<(String, Mesh)> = names.iter().filter_map(build).collect();
+
+    for (name, mesh) in built {
+        cache.meshes.insert(name, meshes.add(mesh));
+    }
 }
 
+/// Resolves the mesh and material of a mesh resource.
+/// `name` is looked up in the `cache` first, to avoid generating a primitive mesh again.
+/// Fails if the resource isn't a mesh, or if its material is missing or not a material.
 pub fn create_mesh_from_data(
+    name: &str,
     resource: &ResourceData,
 
     resources: &HashMap<String, WorldResource>,
     asset_server: &Res<AssetServer>,
-) -> MeshData {
-    let (mesh, material): (MeshInfo, Option<String>) = match resource {
-        ResourceData::BoxMesh(bm) => (
-            MeshInfo::Mesh(Cuboid::new(bm.size[0], bm.size[1], bm.size[2]).into()),
-            bm.material.clone(),
-        ),
-        ResourceData::SphereMesh(sm) => {
-            let mesh = Sphere { radius: sm.radius }.try_into().unwrap();
+    paths: &PathResolver,
+    color_space: ColorSpace,
+    cache: &SceneAssetCache,
+) -> Result<MeshData, LoaderError> {
+    common::plan::check_mesh(name, resource, resources)?;
 
-            (MeshInfo::Mesh(mesh), sm.material.clone())
-        }
-        ResourceData::ArrayMesh(am) => {
-            let path = strip_res_prefix(&am.path);
-            let res: Handle<Mesh> = asset_server.load(path);
+    let material = match resource {
+        ResourceData::BoxMesh(bm) => bm.material.as_ref(),
+        ResourceData::SphereMesh(sm) => sm.material.as_ref(),
+        ResourceData::PrismMesh(pm) => pm.material.as_ref(),
+        ResourceData::TorusMesh(tm) => tm.material.as_ref(),
+        _ => None,
+    };
+
+    let mesh = if let ResourceData::ArrayMesh(am) = resource {
+        let path = paths.resolve(&am.path);
+        let res: Handle<Mesh> = asset_server.load(path);
 
-            (MeshInfo::ArrayMesh(res), None)
+        MeshInfo::ArrayMesh(res)
+    } else if let Some(handle) = cache.meshes.get(name) {
+        MeshInfo::Cached(handle.clone())
+    } else {
+        match create_primitive_mesh(resource) {
+            Some(mesh) => MeshInfo::Mesh(mesh),
+            None => {
+                return Err(LoaderError::InvalidResource {
+                    resource: name.to_owned(),
+                    reason: "is not a mesh".to_owned(),
+                })
+            }
         }
-        _ => panic!("is not mesh"),
     };
 
-    let mut material_info: MaterialInfo = MaterialInfo::Material(Color::WHITE.into());
-    if let Some(mat) = material {
-        let material_data = resources.get(&mat).unwrap();
-        let material = get_material_from_resource(material_data);
-
-        if let Some(albedo_texture) = material.albedo_texture {
-            let res = resources.get(&albedo_texture).unwrap();
+    let (material_info, material_name) = match material {
+        Some(MeshMaterial::Resource(mat)) => {
+            let material_data = resources
+                .get(mat)
+                .ok_or_else(|| LoaderError::MissingMaterial {
+                    mesh: name.to_owned(),
+                    material: mat.clone(),
+                })?;
 
-            if let ResourceData::Texture2D(tex) = &res.data {
-                let texture_handle: Handle<Image> = asset_server.load(strip_res_prefix(&tex.path));
-                material_info = MaterialInfo::Texture(texture_handle);
-            }
-        } else {
-            material_info = MaterialInfo::Material(
-                Color::rgba(
-                    material.albedo_color[0],
-                    material.albedo_color[1],
-                    material.albedo_color[2],
-                    material.albedo_color[3],
-                )
-                .into(),
-            );
+            let info = match &material_data.data {
+                ResourceData::ShaderMaterial(shader) => {
+                    MaterialInfo::from_shader_data(shader, color_space)
+                }
+                ResourceData::ORMMaterial(orm) => {
+                    MaterialInfo::from_orm_data(orm, resources, asset_server, paths, color_space)
+                }
+                _ => {
+                    let material = material_data.as_material().ok_or_else(|| {
+                        LoaderError::InvalidResource {
+                            resource: mat.clone(),
+                            reason: "is not a material".to_owned(),
+                        }
+                    })?;
+                    MaterialInfo::from_data(material, resources, asset_server, paths, color_space)
+                }
+            };
+            (info, Some(mat.clone()))
         }
-    }
+        // Inline materials belong to the mesh, so they are shared by the instances of the mesh.
+        // Resource names are unique, so the mesh name can't clash with a material resource.
+        Some(MeshMaterial::Inline(material)) => (
+            MaterialInfo::from_data(material, resources, asset_server, paths, color_space),
+            Some(name.to_owned()),
+        ),
+        None => (MaterialInfo::default(), None),
+    };
 
-    return MeshData {
+    return Ok(MeshData {
         mesh,
         material: material_info,
-    };
+        material_name,
+    });
 }
 
+/// Same as [create_mesh_from_data], looking up the mesh resource that `entity` references.
 pub fn create_mesh_from_resource(
+    entity: &str,
     mesh_name: String,
     resources: &HashMap<String, WorldResource>,
     asset_server: &Res<AssetServer>,
-) -> MeshData {
-    let resource = if let Some(ok) = resources.get(&mesh_name) {
-        ok
+    paths: &PathResolver,
+    color_space: ColorSpace,
+    cache: &SceneAssetCache,
+) -> Result<MeshData, LoaderError> {
+    let resource = resources
+        .get(&mesh_name)
+        .ok_or_else(|| LoaderError::MissingResource {
+            entity: entity.to_owned(),
+            resource: mesh_name.clone(),
+        })?;
+
+    return create_mesh_from_data(
+        &mesh_name,
+        &resource.data,
+        resources,
+        asset_server,
+        paths,
+        color_space,
+        cache,
+    );
+}
+
+/// Loads the `Texture2D` resource referenced by `texture` through the asset server.
+/// Textures holding non-color data (normal maps, metallic/roughness) must be loaded as `linear`.
+pub fn load_texture(
+    texture: Option<&String>,
+    linear: bool,
+    resources: &HashMap<String, WorldResource>,
+    asset_server: &Res<AssetServer>,
+    paths: &PathResolver,
+) -> Option<Handle<Image>> {
+    let name = texture?;
+    let tex = resources.get(name)?.as_texture()?;
+
+    let path = paths.resolve(&tex.path);
+    if linear {
+        Some(
+            asset_server.load_with_settings(path, |settings: &mut ImageLoaderSettings| {
+                settings.is_srgb = false
+            }),
+        )
     } else {
-        panic!("unable to get mesh");
-    };
+        Some(asset_server.load(path))
+    }
+}
+
+/// Source of the asset handles that the spawned entities reference, see [LoadingAssets]
+/// and [PreparedScene](crate::prepared::PreparedScene).
+pub trait SceneAssets {
+    /// Mesh and material of the mesh resource `name`, which was resolved by
+    /// [plan_entity](common::plan::plan_entity).
+    fn mesh(
+        &mut self,
+        name: &str,
+        resource: &ResourceData,
+        resources: &HashMap<String, WorldResource>,
+    ) -> Result<(Handle<Mesh>, Handle<StandardMaterial>), LoaderError>;
 
-    return create_mesh_from_data(&resource.data, resources, asset_server);
+    /// Scene at the asset path `path`, or `None` if it's not available.
+    fn scene(&mut self, path: String) -> Option<Handle<Scene>>;
+
+    /// Handles of the assets loaded from files so far, see [PendingAssets].
+    fn pending(&self) -> PendingAssets;
+}
+
+/// Creates the assets of a scene while it's being loaded, sharing them through the `cache`.
+pub struct LoadingAssets<'a, 'w> {
+    pub meshes: &'a mut ResMut<'w, Assets<Mesh>>,
+    pub materials: &'a mut ResMut<'w, Assets<StandardMaterial>>,
+    pub asset_server: &'a Res<'w, AssetServer>,
+    pub paths: &'a PathResolver,
+    pub color_space: ColorSpace,
+    pub cache: &'a mut SceneAssetCache,
+    pub pending: PendingAssets,
 }
 
-pub fn get_material_from_resource(resource: &WorldResource) -> StandardMaterialData {
-    // TODO: the actual parsed data could be cached somewhere...
-    if let ResourceData::StandardMaterial(material) = &resource.data {
-        return material.clone();
+impl SceneAssets for LoadingAssets<'_, '_> {
+    fn mesh(
+        &mut self,
+        name: &str,
+        resource: &ResourceData,
+        resources: &HashMap<String, WorldResource>,
+    ) -> Result<(Handle<Mesh>, Handle<StandardMaterial>), LoaderError> {
+        let mesh = create_mesh_from_data(
+            name,
+            resource,
+            resources,
+            self.asset_server,
+            self.paths,
+            self.color_space,
+            self.cache,
+        )?;
+
+        if let MeshInfo::ArrayMesh(handle) = &mesh.mesh {
+            self.pending.push(handle.clone());
+        }
+        for texture in mesh.material.textures.handles() {
+            self.pending.push(texture.clone());
+        }
+
+        // Create the material for this mesh, reusing it if another mesh already did
+        let material = match &mesh.material_name {
+            Some(material_name) => self
+                .cache
+                .materials
+                .entry(material_name.clone())
+                .or_insert_with(|| self.materials.add(mesh.material.to_standard_material()))
+                .clone(),
+            None => self.materials.add(mesh.material.to_standard_material()),
+        };
+
+        // Create the actual mesh, or reuse the one created for the same resource
+        let handle = match mesh.mesh {
+            MeshInfo::Mesh(mh) => self
+                .cache
+                .meshes
+                .entry(name.to_owned())
+                .or_insert_with(|| self.meshes.add(mh))
+                .clone(),
+            MeshInfo::ArrayMesh(handle) | MeshInfo::Cached(handle) => handle,
+        };
+
+        Ok((handle, material))
     }
 
-    panic!();
+    fn scene(&mut self, path: String) -> Option<Handle<Scene>> {
+        let handle = self.asset_server.load(path);
+        self.pending.push(handle.clone());
+        Some(handle)
+    }
+
+    fn pending(&self) -> PendingAssets {
+        self.pending.clone()
+    }
 }
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use bevy::math::Mat4;
+use common::{resources::render::ColorSpace, transform::CoordinateSystem};
+
+use crate::{filter::SpawnFilter, hook::SpawnHook, link::Linker, util::strip_res_prefix};
+
+/// Maps the `res://` paths of a Godot project to Bevy asset paths.
+/// The mapping receives the path with `res://` already stripped, and by default returns it as is,
+/// which assumes that the Godot project root is the Bevy asset root.
+#[derive(Clone, Default)]
+pub struct PathResolver(Option<Arc<dyn Fn(&str) -> String + Send + Sync>>);
+
+impl PathResolver {
+    pub fn new(map: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(map)))
+    }
+
+    /// Resolves a `res://` path to the path to load through the asset server.
+    pub fn resolve(&self, res_path: &String) -> String {
+        let path = strip_res_prefix(res_path);
+        match &self.0 {
+            Some(map) => map(&path),
+            None => path,
+        }
+    }
+}
+
+impl std::fmt::Debug for PathResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(_) => f.write_str("PathResolver(custom)"),
+            None => f.write_str("PathResolver(default)"),
+        }
+    }
+}
+
+/// Options controlling how a scene is loaded into Bevy.
+#[derive(Clone, Debug)]
+pub struct SceneLoadOptions {
+    /// Whether `Camera3D` nodes are spawned as Bevy cameras.
+    pub spawn_cameras: bool,
+
+    /// Whether light nodes are spawned as Bevy lights.
+    pub spawn_lights: bool,
+
+    /// Whether the ambient light and clear color of a `WorldEnvironment` are inserted as
+    /// resources. Either way they are returned in [SpawnResult::environment](crate::SpawnResult).
+    pub apply_environment: bool,
+
+    /// Up-axis convention of the scene, converted to Bevy's Y-up.
+    pub coordinate_system: CoordinateSystem,
+
+    /// Transform applied to the whole scene, after the coordinate system conversion.
+    pub root_transform: Option<Mat4>,
+
+    /// Uniform scale of the whole scene, e.g. 0.5 for a miniature, applied before the root
+    /// transform. It is carried by the transforms like any other scale, so colliders of
+    /// `bevy_rapier` are scaled along. Non-uniform scaling is out of scope. Defaults to 1.0.
+    pub scene_scale: f32,
+
+    /// Prepended to the names of the spawned entities.
+    pub prefix: String,
+
+    /// Whether a [GodotMetadata](crate::components::GodotMetadata) component
+    /// is inserted on every spawned entity.
+    pub insert_metadata: bool,
+
+    /// Maps the paths of meshes, scenes and textures to asset paths.
+    pub path_resolver: PathResolver,
+
+    /// Color space of the material colors that don't specify their own. Godot stores colors
+    /// in sRGB, colors that were already converted to linear look washed out when read as sRGB.
+    pub color_space: ColorSpace,
+
+    /// Label of the scene spawned from glTF files referenced by packed scenes,
+    /// unless the packed scene selects one with its `scene_index`.
+    pub scene_label: String,
+
+    /// Resolve references between entities once the whole scene is spawned.
+    pub linkers: Vec<Linker>,
+
+    /// Called for every entity right after it is spawned, before its children.
+    pub on_spawn: Option<SpawnHook>,
+
+    /// Only spawns the entities that pass the filter, by default all entities are spawned.
+    pub filter: Option<SpawnFilter>,
+}
+
+impl Default for SceneLoadOptions {
+    fn default() -> Self {
+        Self {
+            spawn_cameras: false,
+            spawn_lights: true,
+            apply_environment: true,
+            coordinate_system: CoordinateSystem::default(),
+            root_transform: None,
+            scene_scale: 1.0,
+            prefix: String::new(),
+            insert_metadata: true,
+            path_resolver: PathResolver::default(),
+            color_space: ColorSpace::default(),
+            scene_label: "Scene0".to_owned(),
+            linkers: vec![],
+            on_spawn: None,
+            filter: None,
+        }
+    }
+}
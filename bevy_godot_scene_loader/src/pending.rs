@@ -0,0 +1,51 @@
+use bevy::asset::{AssetServer, LoadState, UntypedHandle};
+
+/// Handles of the assets that a scene loads from files, i.e. array meshes, textures and packed
+/// scenes. They finish loading after the scene is spawned, so the scene can be kept hidden
+/// until [PendingAssets::is_loaded] to avoid pop-in.
+#[derive(Default, Debug, Clone)]
+pub struct PendingAssets {
+    pub handles: Vec<UntypedHandle>,
+}
+
+impl PendingAssets {
+    /// Adds a handle, unless it's already pending.
+    pub fn push(&mut self, handle: impl Into<UntypedHandle>) {
+        let handle = handle.into();
+        if !self.handles.contains(&handle) {
+            self.handles.push(handle);
+        }
+    }
+
+    /// Whether all assets are loaded along with their dependencies.
+    /// Assets that failed to load never are, see [PendingAssets::failed].
+    pub fn is_loaded(&self, asset_server: &AssetServer) -> bool {
+        self.handles
+            .iter()
+            .all(|handle| asset_server.is_loaded_with_dependencies(handle.id()))
+    }
+
+    /// Share of the assets that are loaded along with their dependencies, 1 if there are none.
+    pub fn progress(&self, asset_server: &AssetServer) -> f32 {
+        if self.handles.is_empty() {
+            return 1.0;
+        }
+
+        let loaded = self
+            .handles
+            .iter()
+            .filter(|handle| asset_server.is_loaded_with_dependencies(handle.id()))
+            .count();
+        loaded as f32 / self.handles.len() as f32
+    }
+
+    /// Assets that failed to load, e.g. because their file is missing.
+    pub fn failed<'a>(
+        &'a self,
+        asset_server: &'a AssetServer,
+    ) -> impl Iterator<Item = &'a UntypedHandle> {
+        self.handles
+            .iter()
+            .filter(|handle| asset_server.get_load_state(handle.id()) == Some(LoadState::Failed))
+    }
+}
@@ -1,98 +1,247 @@
-use bevy::ecs::{entity::Entity, system::Commands};
-
-use std::collections::HashMap;
-
-use common::WorldResource;
-use serde_json::Value;
+use bevy::{
+    ecs::{entity::Entity, system::Commands},
+    transform::components::Transform,
+};
+
+use common::{
+    entities::physics::{ColliderMaterial, CollisionShapeData, MassOverride, RigidBodyData},
+    error::LoaderError,
+    metadata::Metadata,
+    ResourceData,
+};
 
 // Bevy Rapier Disabled
 #[cfg(not(feature = "bevy_rapier"))]
-pub fn static_body(commands: &mut Commands) -> Entity {
+pub fn static_body(commands: &mut Commands, _metadata: &Metadata) -> Entity {
     commands.spawn(bevy::prelude::SpatialBundle::default()).id()
 }
 
 #[cfg(not(feature = "bevy_rapier"))]
-pub fn rigid_body(commands: &mut Commands) -> Entity {
+pub fn rigid_body(commands: &mut Commands, _data: &RigidBodyData, _metadata: &Metadata) -> Entity {
     commands.spawn(bevy::prelude::SpatialBundle::default()).id()
 }
 
 #[cfg(not(feature = "bevy_rapier"))]
-pub fn kinematic_body(commands: &mut Commands) -> Entity {
+pub fn kinematic_body(commands: &mut Commands, _metadata: &Metadata) -> Entity {
     commands.spawn(bevy::prelude::SpatialBundle::default()).id()
 }
 
 #[cfg(not(feature = "bevy_rapier"))]
 pub fn collision_shape(
     commands: &mut Commands,
-    _resources: &HashMap<String, WorldResource>,
-    _metadata: &HashMap<String, Value>,
-    _shape: &String,
-) -> Entity {
-    commands.spawn(bevy::prelude::SpatialBundle::default()).id()
+    _shape: &CollisionShapeData,
+    _resource: &ResourceData,
+    _sensor: bool,
+    _material: ColliderMaterial,
+    transform: Transform,
+) -> Result<Entity, LoaderError> {
+    Ok(commands
+        .spawn(bevy::prelude::SpatialBundle::default())
+        .insert(transform)
+        .id())
 }
 
 // Bevy Rapier Implementation
 #[cfg(feature = "bevy_rapier")]
-use bevy_rapier3d::{dynamics::RigidBody, geometry::Collider, geometry::Sensor};
-
+use bevy_rapier3d::{
+    dynamics::{
+        AdditionalMassProperties, Ccd, Damping, GravityScale, LockedAxes, MassProperties,
+        RigidBody, RigidBodyDisabled, Sleeping,
+    },
+    geometry::{Collider, ColliderMassProperties, Friction, Restitution, Sensor},
+};
 #[cfg(feature = "bevy_rapier")]
-use common::ResourceData;
+use common::entities::physics::BodyActivation;
 
+/// Creates the collider of the shape resource `name`.
 #[cfg(feature = "bevy_rapier")]
-pub fn create_collider_from_resource(resource: &ResourceData) -> Collider {
+pub fn create_collider_from_resource(
+    name: &str,
+    resource: &ResourceData,
+) -> Result<Collider, LoaderError> {
     use bevy::math::Vec3;
 
-    match resource {
+    let invalid = |reason: String| LoaderError::InvalidResource {
+        resource: name.to_owned(),
+        reason,
+    };
+
+    let collider = match resource {
         ResourceData::SphereCollisionShape(sh) => Collider::ball(sh.radius),
         ResourceData::BoxCollisionShape(sh) => {
-            Collider::cuboid(sh.size[0] / 2.0, sh.size[1] / 2.0, sh.size[2] / 2.0)
+            let half = Vec3::from(sh.size) / 2.0;
+            Collider::cuboid(half.x, half.y, half.z)
         }
         ResourceData::ConcavePolygonCollisionShape(sh) => {
-            let mut verts = vec![];
-            for i in (0..sh.data.len()).step_by(3) {
-                verts.push(Vec3::new(sh.data[i], sh.data[i + 1], sh.data[i + 2]));
-            }
+            let verts = sh
+                .data
+                .chunks_exact(3)
+                .map(|v| Vec3::new(v[0], v[1], v[2]))
+                .collect();
 
             Collider::polyline(verts, None)
         }
-        _ => panic!("not shape"),
+        ResourceData::HeightMapCollisionShape(sh) => {
+            if !sh.is_valid() {
+                return Err(invalid(format!(
+                    "has a {}x{} grid that doesn't match its {} samples",
+                    sh.width,
+                    sh.depth,
+                    sh.data.len()
+                )));
+            }
+
+            // Rows run along the Z axis, columns along X, and the heights are expected column-major
+            let mut heights = Vec::with_capacity(sh.data.len());
+            for col in 0..sh.width {
+                for row in 0..sh.depth {
+                    heights.push(sh.data[row * sh.width + col]);
+                }
+            }
+
+            let scale = Vec3::new((sh.width - 1) as f32, 1.0, (sh.depth - 1) as f32);
+            Collider::heightfield(heights, sh.depth, sh.width, scale)
+        }
+        ResourceData::WorldBoundaryCollisionShape(sh) => {
+            // Offset onto the plane by the entity transform, see `collider_offset`
+            Collider::halfspace(Vec3::from(sh.normal))
+                .ok_or_else(|| invalid("has a zero normal".to_owned()))?
+        }
+        _ => return Err(invalid("is not a collision shape".to_owned())),
+    };
+
+    Ok(collider)
+}
+
+/// Local offset of the collider from its entity, for shapes that aren't centered on their origin.
+#[cfg(feature = "bevy_rapier")]
+pub fn collider_offset(resource: &ResourceData) -> bevy::math::Vec3 {
+    match resource {
+        ResourceData::WorldBoundaryCollisionShape(sh) => sh.origin().into(),
+        _ => bevy::math::Vec3::ZERO,
     }
 }
 
+/// Whether continuous collision detection was requested with the `ccd` metadata value.
 #[cfg(feature = "bevy_rapier")]
-pub fn static_body(commands: &mut Commands) -> Entity {
+fn ccd_requested(metadata: &Metadata) -> bool {
+    metadata.get("ccd").and_then(|x| x.as_bool()) == Some(true)
+}
+
+#[cfg(feature = "bevy_rapier")]
+pub fn static_body(commands: &mut Commands, metadata: &Metadata) -> Entity {
+    if ccd_requested(metadata) {
+        bevy::log::debug!("ignoring ccd on a static body, it never moves");
+    }
+
     commands.spawn(RigidBody::Fixed).id()
 }
 
 #[cfg(feature = "bevy_rapier")]
-pub fn rigid_body(commands: &mut Commands) -> Entity {
-    commands.spawn(RigidBody::Dynamic).id()
+pub fn rigid_body(commands: &mut Commands, data: &RigidBodyData, metadata: &Metadata) -> Entity {
+    let mut builder = commands.spawn(RigidBody::Dynamic);
+    if ccd_requested(metadata) {
+        builder.insert(Ccd::enabled());
+    }
+
+    // Only override the rapier defaults with properties that were exported
+    if let Some(gravity_scale) = data.gravity_scale {
+        builder.insert(GravityScale(gravity_scale));
+    }
+    if data.linear_damp.is_some() || data.angular_damp.is_some() {
+        builder.insert(Damping {
+            linear_damping: data.linear_damp.unwrap_or(0.0),
+            angular_damping: data.angular_damp.unwrap_or(0.0),
+        });
+    }
+    if let Some(axis_lock) = data.axis_lock {
+        builder.insert(LockedAxes::from_bits_truncate(axis_lock));
+    }
+
+    let mut sleeping = match data.can_sleep {
+        Some(false) => Some(Sleeping::disabled()),
+        _ => None,
+    };
+    match data.activation() {
+        BodyActivation::Awake => {}
+        BodyActivation::Asleep => sleeping.get_or_insert_with(Sleeping::default).sleeping = true,
+        BodyActivation::Disabled => {
+            builder.insert(RigidBodyDisabled);
+        }
+    }
+    if let Some(sleeping) = sleeping {
+        builder.insert(sleeping);
+    }
+
+    // Same metadata as the rapier loader, falling back to the mass of the colliders
+    match MassOverride::from_metadata(metadata) {
+        Ok(Some(mass)) => {
+            builder.insert(AdditionalMassProperties::MassProperties(MassProperties {
+                local_center_of_mass: mass.center_of_mass.into(),
+                mass: mass.mass,
+                principal_inertia: mass.principal_inertia.into(),
+                ..Default::default()
+            }));
+        }
+        Ok(None) => {}
+        Err(reason) => bevy::log::warn!("ignoring mass properties: {}", reason),
+    }
+
+    builder.id()
 }
 
 #[cfg(feature = "bevy_rapier")]
-pub fn kinematic_body(commands: &mut Commands) -> Entity {
-    commands.spawn(RigidBody::KinematicVelocityBased).id()
+pub fn kinematic_body(commands: &mut Commands, metadata: &Metadata) -> Entity {
+    let mut builder = commands.spawn(RigidBody::KinematicVelocityBased);
+    if ccd_requested(metadata) {
+        builder.insert(Ccd::enabled());
+    }
+
+    builder.id()
 }
 
 #[cfg(feature = "bevy_rapier")]
 pub fn collision_shape(
     commands: &mut Commands,
-    resources: &HashMap<String, WorldResource>,
-    metadata: &HashMap<String, Value>,
-    shape: &String,
-) -> Entity {
+    shape: &CollisionShapeData,
+    resource: &ResourceData,
+    sensor: bool,
+    material: ColliderMaterial,
+    transform: Transform,
+) -> Result<Entity, LoaderError> {
     use bevy::ecs::system::EntityCommands;
 
-    let resource = resources.get(shape).unwrap();
-    let collider = create_collider_from_resource(&resource.data);
+    // Disabled shapes keep their entity, so that their children are still spawned
+    let collider = if shape.is_disabled() {
+        None
+    } else {
+        Some(create_collider_from_resource(&shape.shape, resource)?)
+    };
+    let offset = Transform::from_translation(collider_offset(resource));
+
+    let mut builder: &mut EntityCommands = &mut commands.spawn(bevy::prelude::SpatialBundle {
+        transform: transform * offset,
+        ..Default::default()
+    });
+
+    if let Some(collider) = collider {
+        builder = builder.insert(collider);
+    }
 
-    let mut builder: &mut EntityCommands = &mut commands.spawn(collider);
+    if sensor {
+        builder = builder.insert(Sensor);
+    }
 
-    if let Some(sensor_value) = metadata.get("sensor") {
-        if let Some(true) = sensor_value.as_bool() {
-            builder = builder.insert(Sensor);
-        }
+    // Same metadata as the rapier loader, so that both backends simulate alike
+    if let Some(friction) = material.friction {
+        builder = builder.insert(Friction::coefficient(friction));
+    }
+    if let Some(restitution) = material.restitution {
+        builder = builder.insert(Restitution::coefficient(restitution));
+    }
+    if let Some(density) = material.density {
+        builder = builder.insert(ColliderMassProperties::Density(density));
     }
 
-    builder.id()
+    Ok(builder.id())
 }
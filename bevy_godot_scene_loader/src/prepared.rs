@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetServer, Assets, Handle},
+    ecs::system::{Commands, Res, ResMut},
+    math::Mat4,
+    pbr::StandardMaterial,
+    render::mesh::Mesh,
+    scene::Scene,
+};
+use common::{
+    error::LoaderError,
+    plan::{plan_scene, SpawnPlan},
+    transform::TransformError,
+    ResourceData, SceneWorld, WorldResource,
+};
+
+use crate::{
+    mesh::{prebuild_primitive_meshes, LoadingAssets, SceneAssetCache, SceneAssets},
+    options::SceneLoadOptions,
+    pending::PendingAssets,
+    scene_asset_path, spawn_world, SpawnResult,
+};
+
+/// A [SceneWorld] whose meshes, materials and scenes were created up front, so that it can be
+/// spawned many times with [instantiate] while all instances share the same assets.
+pub struct PreparedScene {
+    world: SceneWorld,
+    options: SceneLoadOptions,
+
+    /// Mesh and material handles keyed by the mesh resource name.
+    meshes: HashMap<String, (Handle<Mesh>, Handle<StandardMaterial>)>,
+
+    /// Scene handles keyed by their asset path.
+    scenes: HashMap<String, Handle<Scene>>,
+
+    /// Assets loaded from files, shared by all instances.
+    pending: PendingAssets,
+}
+
+impl PreparedScene {
+    /// Creates the assets of all entities in `world`. Entities that fail to load are reported
+    /// in the [SpawnResult] of every instance instead.
+    pub fn new(
+        world: &SceneWorld,
+        options: &SceneLoadOptions,
+
+        meshes: &mut ResMut<Assets<Mesh>>,
+        materials: &mut ResMut<Assets<StandardMaterial>>,
+        assets: &Res<AssetServer>,
+    ) -> Self {
+        let mut cache = SceneAssetCache::default();
+        prebuild_primitive_meshes(world, meshes, &mut cache);
+
+        let mut loading = LoadingAssets {
+            meshes,
+            materials,
+            asset_server: assets,
+            paths: &options.path_resolver,
+            color_space: options.color_space,
+            cache: &mut cache,
+            pending: PendingAssets::default(),
+        };
+
+        let mut prepared_meshes = HashMap::new();
+        let mut scenes = HashMap::new();
+        for entry in plan_scene(world) {
+            match entry.plan {
+                Ok(SpawnPlan::Mesh { instance, resource }) => {
+                    if prepared_meshes.contains_key(&instance.mesh) {
+                        continue;
+                    }
+
+                    if let Ok(handles) = loading.mesh(&instance.mesh, resource, &world.resources) {
+                        prepared_meshes.insert(instance.mesh.clone(), handles);
+                    }
+                }
+                Ok(SpawnPlan::GridMap(grid)) => {
+                    for name in grid.mesh_library.values() {
+                        let resource = &world.resources[name].data;
+                        if !resource.is_mesh() || prepared_meshes.contains_key(name) {
+                            continue;
+                        }
+
+                        if let Ok(handles) = loading.mesh(name, resource, &world.resources) {
+                            prepared_meshes.insert(name.clone(), handles);
+                        }
+                    }
+                }
+                Ok(SpawnPlan::Scene(scene)) => {
+                    let path = options.path_resolver.resolve(&scene.path);
+                    if let Some(path) = scene_asset_path(path, scene.scene_index, options) {
+                        if let Some(handle) = loading.scene(path.clone()) {
+                            scenes.insert(path, handle);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            world: SceneWorld {
+                entities: world.entities.clone(),
+                resources: world.resources.clone(),
+                errors: world.errors.clone(),
+            },
+            options: options.clone(),
+            meshes: prepared_meshes,
+            scenes,
+            pending: loading.pending,
+        }
+    }
+
+    pub fn world(&self) -> &SceneWorld {
+        &self.world
+    }
+}
+
+impl SceneAssets for &PreparedScene {
+    fn mesh(
+        &mut self,
+        name: &str,
+        _resource: &ResourceData,
+        _resources: &HashMap<String, WorldResource>,
+    ) -> Result<(Handle<Mesh>, Handle<StandardMaterial>), LoaderError> {
+        self.meshes
+            .get(name)
+            .cloned()
+            .ok_or_else(|| LoaderError::InvalidResource {
+                resource: name.to_owned(),
+                reason: "could not be prepared".to_owned(),
+            })
+    }
+
+    fn scene(&mut self, path: String) -> Option<Handle<Scene>> {
+        self.scenes.get(&path).cloned()
+    }
+
+    fn pending(&self) -> PendingAssets {
+        self.pending.clone()
+    }
+}
+
+/// Spawns another instance of a [PreparedScene], placed with the `transform` root matrix on top
+/// of the root transform of its [SceneLoadOptions]. Only entities are spawned, all instances
+/// reference the same assets.
+pub fn instantiate(
+    prepared: &PreparedScene,
+    transform: Mat4,
+    commands: &mut Commands,
+) -> Result<SpawnResult, TransformError> {
+    let root = prepared.options.root_transform.unwrap_or(Mat4::IDENTITY);
+    let options = SceneLoadOptions {
+        root_transform: Some(transform * root),
+        ..prepared.options.clone()
+    };
+
+    let mut assets = prepared;
+    spawn_world(&prepared.world, &options, commands, &mut assets)
+}
@@ -0,0 +1,264 @@
+use std::f32::consts::TAU;
+
+use bevy::{
+    math::Vec3,
+    render::{
+        mesh::{Indices, Mesh},
+        render_asset::RenderAssetUsages,
+        render_resource::PrimitiveTopology,
+    },
+};
+
+/// Builds a flat shaded mesh out of convex polygons, given counter-clockwise from the outside.
+fn mesh_from_faces(faces: &[&[Vec3]]) -> Mesh {
+    let mut positions: Vec<[f32; 3]> = vec![];
+    let mut normals: Vec<[f32; 3]> = vec![];
+    let mut uvs: Vec<[f32; 2]> = vec![];
+    let mut indices: Vec<u32> = vec![];
+
+    for face in faces {
+        let normal = (face[1] - face[0])
+            .cross(face[2] - face[0])
+            .normalize_or_zero();
+        let start = positions.len() as u32;
+
+        for (i, vertex) in face.iter().enumerate() {
+            positions.push(vertex.to_array());
+            normals.push(normal.to_array());
+            uvs.push(match i {
+                0 => [0.0, 1.0],
+                1 => [1.0, 1.0],
+                2 => [1.0, 0.0],
+                _ => [0.0, 0.0],
+            });
+        }
+
+        // Triangle fan around the first vertex
+        for i in 1..face.len() as u32 - 1 {
+            indices.extend_from_slice(&[start, start + i, start + i + 1]);
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Box whose faces are split into a grid, like Godot's `BoxMesh` with `subdivide_*` set.
+/// `subdivisions` is the number of extra cuts along the X, Y and Z axes.
+pub fn subdivided_box_mesh(size: Vec3, subdivisions: [usize; 3]) -> Mesh {
+    let mut positions: Vec<[f32; 3]> = vec![];
+    let mut normals: Vec<[f32; 3]> = vec![];
+    let mut uvs: Vec<[f32; 2]> = vec![];
+    let mut indices: Vec<u32> = vec![];
+
+    // Cuts along a world axis
+    let cuts = |axis: Vec3| {
+        let axis = axis.abs();
+        if axis.x > 0.0 {
+            subdivisions[0]
+        } else if axis.y > 0.0 {
+            subdivisions[1]
+        } else {
+            subdivisions[2]
+        }
+    };
+
+    // Normal of each face, with the U and V axes running counter-clockwise around it
+    let faces = [
+        (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+        (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+        (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+        (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+        (Vec3::Z, Vec3::X, Vec3::Y),
+        (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+    ];
+
+    for (normal, u, v) in faces {
+        let center = normal * size.dot(normal.abs()) / 2.0;
+        let (u_extent, v_extent) = (size.dot(u.abs()), size.dot(v.abs()));
+        let (u_segments, v_segments) = (cuts(u) + 1, cuts(v) + 1);
+
+        let start = positions.len() as u32;
+        let row = u_segments as u32 + 1;
+
+        for j in 0..=v_segments {
+            let t = j as f32 / v_segments as f32;
+            for i in 0..=u_segments {
+                let s = i as f32 / u_segments as f32;
+
+                let position = center + u * (s - 0.5) * u_extent + v * (t - 0.5) * v_extent;
+                positions.push(position.to_array());
+                normals.push(normal.to_array());
+                uvs.push([s, 1.0 - t]);
+            }
+        }
+
+        for j in 0..v_segments as u32 {
+            for i in 0..u_segments as u32 {
+                let a = start + j * row + i;
+                indices.extend_from_slice(&[a, a + 1, a + row + 1, a, a + row + 1, a + row]);
+            }
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Triangular prism extruded along Z, like Godot's `PrismMesh`.
+/// `left_to_right` positions the apex between the left (0.0) and right (1.0) edge.
+pub fn prism_mesh(size: Vec3, left_to_right: f32) -> Mesh {
+    let half = size / 2.0;
+    let apex_x = -half.x + left_to_right * size.x;
+
+    let front = [
+        Vec3::new(-half.x, -half.y, half.z),
+        Vec3::new(half.x, -half.y, half.z),
+        Vec3::new(apex_x, half.y, half.z),
+    ];
+    let back = [
+        Vec3::new(-half.x, -half.y, -half.z),
+        Vec3::new(apex_x, half.y, -half.z),
+        Vec3::new(half.x, -half.y, -half.z),
+    ];
+    let bottom = [
+        Vec3::new(-half.x, -half.y, half.z),
+        Vec3::new(-half.x, -half.y, -half.z),
+        Vec3::new(half.x, -half.y, -half.z),
+        Vec3::new(half.x, -half.y, half.z),
+    ];
+    let right = [
+        Vec3::new(half.x, -half.y, half.z),
+        Vec3::new(half.x, -half.y, -half.z),
+        Vec3::new(apex_x, half.y, -half.z),
+        Vec3::new(apex_x, half.y, half.z),
+    ];
+    let left = [
+        Vec3::new(-half.x, -half.y, -half.z),
+        Vec3::new(-half.x, -half.y, half.z),
+        Vec3::new(apex_x, half.y, half.z),
+        Vec3::new(apex_x, half.y, -half.z),
+    ];
+
+    mesh_from_faces(&[&front, &back, &bottom, &right, &left])
+}
+
+/// Torus lying in the XZ plane, like Godot's `TorusMesh`.
+/// `rings` is the number of slices around the Y axis, `ring_segments` the number of segments
+/// around the tube of each slice.
+pub fn torus_mesh(
+    inner_radius: f32,
+    outer_radius: f32,
+    rings: usize,
+    ring_segments: usize,
+) -> Mesh {
+    let rings = rings.max(3);
+    let ring_segments = ring_segments.max(3);
+
+    let major_radius = (inner_radius + outer_radius) / 2.0;
+    let minor_radius = (outer_radius - inner_radius) / 2.0;
+
+    let vertex_count = (rings + 1) * (ring_segments + 1);
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(vertex_count);
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(vertex_count);
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(vertex_count);
+
+    for ring in 0..=rings {
+        let u = ring as f32 / rings as f32;
+        let (sin_u, cos_u) = (u * TAU).sin_cos();
+
+        for segment in 0..=ring_segments {
+            let v = segment as f32 / ring_segments as f32;
+            let (sin_v, cos_v) = (v * TAU).sin_cos();
+
+            let distance = major_radius + minor_radius * cos_v;
+            positions.push([distance * cos_u, minor_radius * sin_v, distance * sin_u]);
+            normals.push([cos_v * cos_u, sin_v, cos_v * sin_u]);
+            uvs.push([u, v]);
+        }
+    }
+
+    let stride = ring_segments as u32 + 1;
+    let mut indices: Vec<u32> = Vec::with_capacity(rings * ring_segments * 6);
+    for ring in 0..rings as u32 {
+        for segment in 0..ring_segments as u32 {
+            let a = ring * stride + segment;
+            let b = (ring + 1) * stride + segment;
+            let c = (ring + 1) * stride + segment + 1;
+            let d = ring * stride + segment + 1;
+
+            indices.extend_from_slice(&[a, d, b, d, c, b]);
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{math::Vec3A, render::mesh::VertexAttributeValues};
+
+    use super::*;
+
+    fn vertex_count(mesh: &Mesh) -> usize {
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions.len(),
+            _ => panic!("mesh should have positions"),
+        }
+    }
+
+    fn assert_near(actual: Vec3A, expected: Vec3, what: &str) {
+        assert!(
+            (actual - Vec3A::from(expected)).length() < 1.0e-5,
+            "{} is {}, expected {}",
+            what,
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn prism_mesh_has_flat_faces_and_fills_its_size() {
+        let mesh = prism_mesh(Vec3::new(2.0, 1.0, 4.0), 0.5);
+
+        // Two triangles and three quads, with their own vertices for flat normals
+        assert_eq!(vertex_count(&mesh), 3 + 3 + 4 * 3);
+        assert_eq!(mesh.indices().unwrap().len(), (1 + 1 + 2 * 3) * 3);
+
+        let aabb = mesh.compute_aabb().unwrap();
+        assert_near(aabb.center, Vec3::ZERO, "center");
+        assert_near(aabb.half_extents, Vec3::new(1.0, 0.5, 2.0), "half extents");
+    }
+
+    #[test]
+    fn torus_mesh_has_a_vertex_per_ring_and_segment() {
+        let mesh = torus_mesh(1.0, 2.0, 8, 8);
+
+        // The seams are duplicated so that the UVs wrap around
+        assert_eq!(vertex_count(&mesh), 9 * 9);
+        assert_eq!(mesh.indices().unwrap().len(), 8 * 8 * 6);
+
+        let aabb = mesh.compute_aabb().unwrap();
+        assert_near(aabb.center, Vec3::ZERO, "center");
+        assert_near(aabb.half_extents, Vec3::new(2.0, 0.5, 2.0), "half extents");
+    }
+}
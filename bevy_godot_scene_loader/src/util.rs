@@ -1,30 +1,57 @@
-use bevy::{math::Mat4, transform::components::Transform};
-use common::EntityData;
+use bevy::{math::Mat4, render::view::RenderLayers, transform::components::Transform};
+use common::{
+    transform::{to_matrix_columns, TransformError},
+    EntityData,
+};
 
 pub fn strip_res_prefix(str: &String) -> String {
     return str.replace("res://", "");
 }
 
-pub fn vec_to_transform(transform: &Vec<f32>) -> Transform {
-    let slice = &transform[0..16];
-    Transform::from_matrix(Mat4::from_cols_array(slice.try_into().unwrap()))
+/// Bevy render layers of a Godot layer bitmask, with Godot's layer 1 as Bevy's default layer 0.
+/// Returns `None` for an empty mask, which keeps the default layer.
+pub fn render_layers(mask: u32) -> Option<RenderLayers> {
+    if mask == 0 {
+        return None;
+    }
+
+    let layers: Vec<u8> = (0..u32::BITS as u8)
+        .filter(|layer| mask & (1 << layer) != 0)
+        .collect();
+    Some(RenderLayers::from_layers(&layers))
+}
+
+pub fn vec_to_transform(transform: &Vec<f32>) -> Result<Transform, TransformError> {
+    let columns = to_matrix_columns(transform)?;
+    Ok(Transform::from_matrix(Mat4::from_cols_array(&columns)))
 }
 
 macro_rules! transform {
     ($var:expr) => {
-        Some(vec_to_transform(&$var.transform))
+        Some(vec_to_transform(&$var.transform)?)
     };
 }
 
-pub fn get_transform_from_data(data: &EntityData) -> Option<Transform> {
-    match data {
+pub fn get_transform_from_data(data: &EntityData) -> Result<Option<Transform>, TransformError> {
+    Ok(match data {
         EntityData::MeshInstance3D(data) => transform!(data),
         EntityData::Node3D(data) => transform!(data),
         EntityData::StaticBody3D(data) => transform!(data),
         EntityData::RigidBody3D(data) => transform!(data),
         EntityData::KinematicBody3D(data) => transform!(data),
         EntityData::CollisionShape3D(data) => transform!(data),
+        EntityData::Area3D(data) => transform!(data),
+        EntityData::PinJoint3D(data) => transform!(data),
+        EntityData::HingeJoint3D(data) => transform!(data),
         EntityData::ModelScene(data) => transform!(data),
+        EntityData::Label3D(data) => transform!(data),
+        EntityData::Skeleton3D(data) => transform!(data),
+        EntityData::GridMap(data) => transform!(data),
         EntityData::Camera(data) => transform!(data),
-    }
+        EntityData::OmniLight3D(data) => transform!(data),
+        EntityData::SpotLight3D(data) => transform!(data),
+        EntityData::AnimationPlayer(_)
+        | EntityData::WorldEnvironment(_)
+        | EntityData::Unknown { .. } => None,
+    })
 }
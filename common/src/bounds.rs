@@ -0,0 +1,202 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    transform::{mul_matrix_columns, to_matrix_columns, IDENTITY_TRANSFORM},
+    EntityData, ResourceData, SceneWorld, WorldEntity,
+};
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl Aabb {
+    /// Box centered at the origin.
+    pub fn from_half_extents(half_extents: [f32; 3]) -> Self {
+        let [x, y, z] = half_extents;
+        Self {
+            min: [-x, -y, -z],
+            max: [x, y, z],
+        }
+    }
+
+    /// Smallest box containing all points, `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = [f32; 3]>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|point| Self {
+                min: point,
+                max: point,
+            })
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: core::array::from_fn(|i| self.min[i].min(other.min[i])),
+            max: core::array::from_fn(|i| self.max[i].max(other.max[i])),
+        }
+    }
+
+    /// Whether the point is inside the box or on its boundary.
+    pub fn contains(&self, point: [f32; 3]) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    pub fn center(&self) -> [f32; 3] {
+        core::array::from_fn(|i| (self.min[i] + self.max[i]) * 0.5)
+    }
+
+    pub fn size(&self) -> [f32; 3] {
+        core::array::from_fn(|i| self.max[i] - self.min[i])
+    }
+
+    /// Box containing this one after applying a column-major 4x4 transform.
+    pub fn transformed(&self, matrix: &[f32; 16]) -> Aabb {
+        let corners = (0..8).map(|corner| {
+            let local: [f32; 3] = core::array::from_fn(|i| match corner & (1 << i) {
+                0 => self.min[i],
+                _ => self.max[i],
+            });
+            core::array::from_fn(|row| {
+                (0..3)
+                    .map(|col| matrix[col * 4 + row] * local[col])
+                    .sum::<f32>()
+                    + matrix[12 + row]
+            })
+        });
+
+        Aabb::from_points(corners).expect("a box should have corners")
+    }
+}
+
+/// Spatial extent of a scene, see [SceneWorld::bounds].
+#[derive(Debug, Clone, Default)]
+pub struct SceneBounds {
+    /// Union of the extents of all entities that have known extents, `None` if none have.
+    pub aabb: Option<Aabb>,
+
+    /// Entities left out because their extent can't be known from the scene alone, e.g. array
+    /// meshes and packed scenes whose geometry is in other files, or infinite world boundaries.
+    pub unknown: Vec<String>,
+}
+
+impl SceneWorld {
+    /// Bounds of all meshes and collision shapes of the scene, in the space of the scene root.
+    /// See [SceneWorld::bounds] for the entities that had to be left out.
+    pub fn compute_bounds(&self) -> Option<Aabb> {
+        self.bounds().aabb
+    }
+
+    /// Walks the entity tree, composing the transforms, and unions the extents of the primitive
+    /// meshes and collision shapes.
+    pub fn bounds(&self) -> SceneBounds {
+        let mut bounds = SceneBounds::default();
+        let identity = to_matrix_columns::<f32>(&IDENTITY_TRANSFORM)
+            .expect("identity transform should be valid");
+
+        for entity in &self.entities {
+            self.add_bounds(entity, &identity, &mut bounds);
+        }
+
+        bounds
+    }
+
+    fn add_bounds(&self, entity: &WorldEntity, parent: &[f32; 16], bounds: &mut SceneBounds) {
+        // Entities with a malformed transform can't be placed, neither can their children
+        let Ok(local) = to_matrix_columns::<f32>(entity.data.transform()) else {
+            bounds.unknown.push(entity.name.clone());
+            return;
+        };
+        let transform = mul_matrix_columns(parent, &local);
+
+        let mut add = |aabb: Aabb, matrix: &[f32; 16]| {
+            let aabb = aabb.transformed(matrix);
+            bounds.aabb = Some(match &bounds.aabb {
+                Some(existing) => existing.union(&aabb),
+                None => aabb,
+            });
+        };
+
+        let known = match &entity.data {
+            EntityData::MeshInstance3D(instance) => self
+                .resources
+                .get(&instance.mesh)
+                .and_then(|x| resource_bounds(&x.data))
+                .map(|aabb| add(aabb, &transform))
+                .is_some(),
+            EntityData::CollisionShape3D(shape) => self
+                .resources
+                .get(&shape.shape)
+                .and_then(|x| resource_bounds(&x.data))
+                .map(|aabb| add(aabb, &transform))
+                .is_some(),
+            EntityData::GridMap(grid) => {
+                let mut known = true;
+                for (cell, name) in grid.items() {
+                    let aabb = self
+                        .resources
+                        .get(name)
+                        .and_then(|x| resource_bounds(&x.data));
+                    match aabb {
+                        Some(aabb) => add(
+                            aabb,
+                            &mul_matrix_columns(&transform, &grid.cell_transform(cell)),
+                        ),
+                        None => known = false,
+                    }
+                }
+                known
+            }
+            EntityData::ModelScene(scene) => scene.type_name != "MODEL",
+            _ => true,
+        };
+
+        if !known {
+            bounds.unknown.push(entity.name.clone());
+        }
+
+        for child in entity.children.iter().flatten() {
+            self.add_bounds(child, &transform, bounds);
+        }
+    }
+}
+
+/// Local extent of a mesh or collision shape, `None` for resources without a known extent.
+pub fn resource_bounds(resource: &ResourceData) -> Option<Aabb> {
+    let half = |[x, y, z]: [f32; 3]| Some(Aabb::from_half_extents([x * 0.5, y * 0.5, z * 0.5]));
+
+    match resource {
+        ResourceData::BoxMesh(mesh) => half(mesh.size),
+        ResourceData::PrismMesh(mesh) => half(mesh.size),
+        ResourceData::SphereMesh(mesh) => Some(Aabb::from_half_extents([mesh.radius; 3])),
+        // The tube of the torus is centered between the two radii
+        ResourceData::TorusMesh(mesh) => {
+            let tube = (mesh.outer_radius - mesh.inner_radius) * 0.5;
+            let radius = mesh.outer_radius;
+            Some(Aabb::from_half_extents([radius, tube, radius]))
+        }
+        ResourceData::BoxCollisionShape(shape) => half(shape.size),
+        ResourceData::SphereCollisionShape(shape) => {
+            Some(Aabb::from_half_extents([shape.radius; 3]))
+        }
+        ResourceData::ConcavePolygonCollisionShape(shape) => {
+            Aabb::from_points(shape.data.chunks_exact(3).map(|x| [x[0], x[1], x[2]]))
+        }
+        // Centered on the origin, one unit between samples
+        ResourceData::HeightMapCollisionShape(shape) if shape.is_valid() => {
+            let heights = shape.data.iter().copied();
+            let min = heights.clone().fold(f32::INFINITY, f32::min);
+            let max = heights.fold(f32::NEG_INFINITY, f32::max);
+            let x = (shape.width - 1) as f32 * 0.5;
+            let z = (shape.depth - 1) as f32 * 0.5;
+            Some(Aabb {
+                min: [-x, min, -z],
+                max: [x, max, z],
+            })
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,240 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    entities::{
+        node::Node3DData,
+        physics::{Area3DData, CollisionShapeData, RigidBodyData, StaticBodyData},
+        render::MeshInstanceData,
+    },
+    metadata::{Metadata, MetadataValue},
+    resources::{
+        physics::{BoxCollisionShapeData, SphereCollisionShapeData},
+        render::{BoxMeshData, SphereMeshData},
+    },
+    transform::IDENTITY_TRANSFORM,
+    EntityData, HashMap, ResourceData, SceneWorld, WorldEntity, WorldResource,
+};
+
+/// Builds a [SceneWorld] in code, e.g. for tests or generated levels, producing the same data
+/// as parsing the equivalent scene file.
+///
+/// `add_*` methods add a root entity, and `with_*` methods add to the root entity added last.
+/// Names of the resources and of the generated children are allocated by the builder.
+///
+/// ```
+/// # use common::builder::{translation, SceneWorldBuilder};
+/// let world = SceneWorldBuilder::new()
+///     .add_static_body("Ground", translation([0.0, -1.0, 0.0]))
+///     .with_box_collider([20.0, 1.0, 20.0])
+///     .with_box_mesh([20.0, 1.0, 20.0])
+///     .add_rigid_body("Ball", translation([0.0, 5.0, 0.0]))
+///     .with_sphere_collider(0.5)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SceneWorldBuilder {
+    entities: Vec<WorldEntity>,
+    resources: HashMap<String, WorldResource>,
+    next_id: usize,
+}
+
+/// Serialized transform that only translates, in Godot's `Transform3D` layout.
+pub fn translation(translation: [f32; 3]) -> [f32; 12] {
+    let mut transform = IDENTITY_TRANSFORM;
+    transform[9..].copy_from_slice(&translation);
+    transform
+}
+
+impl SceneWorldBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a root entity of any type, its Godot type is taken from `data`.
+    pub fn add_entity(mut self, name: impl Into<String>, data: EntityData) -> Self {
+        let entity = Self::entity(name.into(), data);
+        self.entities.push(entity);
+        self
+    }
+
+    /// Adds a `Node3D`, `transform` is in Godot's `Transform3D` layout.
+    pub fn add_node(self, name: impl Into<String>, transform: [f32; 12]) -> Self {
+        self.add_entity(
+            name,
+            EntityData::Node3D(Node3DData {
+                transform: transform.to_vec(),
+                visible: None,
+                process_mode: None,
+                process_flags: None,
+            }),
+        )
+    }
+
+    pub fn add_static_body(self, name: impl Into<String>, transform: [f32; 12]) -> Self {
+        self.add_entity(
+            name,
+            EntityData::StaticBody3D(StaticBodyData {
+                transform: transform.to_vec(),
+                visible: None,
+            }),
+        )
+    }
+
+    pub fn add_rigid_body(self, name: impl Into<String>, transform: [f32; 12]) -> Self {
+        self.add_entity(
+            name,
+            EntityData::RigidBody3D(RigidBodyData {
+                transform: transform.to_vec(),
+                visible: None,
+                linear_velocity: None,
+                angular_velocity: None,
+                gravity_scale: None,
+                linear_damp: None,
+                angular_damp: None,
+                axis_lock: None,
+                freeze: None,
+                sleeping: None,
+                can_sleep: None,
+                process_mode: None,
+            }),
+        )
+    }
+
+    pub fn add_area(self, name: impl Into<String>, transform: [f32; 12]) -> Self {
+        self.add_entity(
+            name,
+            EntityData::Area3D(Area3DData {
+                transform: transform.to_vec(),
+            }),
+        )
+    }
+
+    /// Adds a resource under a newly allocated name, which is returned to reference it.
+    pub fn add_resource(&mut self, data: ResourceData) -> String {
+        let name = format!("{}_{}", data.type_name(), self.allocate_id());
+        self.resources.insert(name.clone(), WorldResource { data });
+        name
+    }
+
+    /// Adds a child of any type to the last root entity.
+    ///
+    /// Panics if no entity was added yet.
+    pub fn with_child(mut self, name: impl Into<String>, data: EntityData) -> Self {
+        let child = Self::entity(name.into(), data);
+        self.last_entity()
+            .children
+            .get_or_insert_with(Vec::new)
+            .push(child);
+        self
+    }
+
+    /// Adds a metadata entry to the last root entity.
+    ///
+    /// Panics if no entity was added yet.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: MetadataValue) -> Self {
+        self.last_entity().metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Adds a `CollisionShape3D` child with a box shape of `size` to the last root entity.
+    pub fn with_box_collider(self, size: [f32; 3]) -> Self {
+        let shape = ResourceData::BoxCollisionShape(BoxCollisionShapeData { size });
+        self.with_collider(shape)
+    }
+
+    /// Adds a `CollisionShape3D` child with a sphere shape of `radius` to the last root entity.
+    pub fn with_sphere_collider(self, radius: f32) -> Self {
+        let shape = ResourceData::SphereCollisionShape(SphereCollisionShapeData { radius });
+        self.with_collider(shape)
+    }
+
+    /// Adds a `MeshInstance3D` child with a box mesh of `size` to the last root entity.
+    pub fn with_box_mesh(self, size: [f32; 3]) -> Self {
+        let mesh = ResourceData::BoxMesh(BoxMeshData {
+            size,
+            subdivide_width: None,
+            subdivide_height: None,
+            subdivide_depth: None,
+            material: None,
+        });
+        self.with_mesh(mesh)
+    }
+
+    /// Adds a `MeshInstance3D` child with a sphere mesh of `radius` to the last root entity.
+    pub fn with_sphere_mesh(self, radius: f32) -> Self {
+        let mesh = ResourceData::SphereMesh(SphereMeshData {
+            radius,
+            radial_segments: None,
+            rings: None,
+            material: None,
+        });
+        self.with_mesh(mesh)
+    }
+
+    pub fn build(self) -> SceneWorld {
+        SceneWorld {
+            entities: self.entities,
+            resources: self.resources,
+            errors: Vec::new(),
+        }
+    }
+
+    fn with_collider(mut self, shape: ResourceData) -> Self {
+        let shape = self.add_resource(shape);
+        let name = self.child_name("CollisionShape3D");
+        self.with_child(
+            name,
+            EntityData::CollisionShape3D(CollisionShapeData {
+                shape,
+                transform: IDENTITY_TRANSFORM.to_vec(),
+                disabled: None,
+            }),
+        )
+    }
+
+    fn with_mesh(mut self, mesh: ResourceData) -> Self {
+        let mesh = self.add_resource(mesh);
+        let name = self.child_name("MeshInstance3D");
+        self.with_child(
+            name,
+            EntityData::MeshInstance3D(MeshInstanceData {
+                mesh,
+                visible: true,
+                transform: IDENTITY_TRANSFORM.to_vec(),
+                visibility_layer: None,
+                cast_shadow: None,
+            }),
+        )
+    }
+
+    fn entity(name: String, data: EntityData) -> WorldEntity {
+        WorldEntity {
+            name,
+            entity_type: data.type_name().to_string(),
+            data,
+            metadata: Metadata::default(),
+            children: None,
+        }
+    }
+
+    /// Entity names are unique in a scene, so generated children are named after their parent.
+    fn child_name(&mut self, entity_type: &str) -> String {
+        let id = self.allocate_id();
+        format!("{}_{}{}", self.last_entity().name, entity_type, id)
+    }
+
+    fn allocate_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn last_entity(&mut self) -> &mut WorldEntity {
+        self.entities
+            .last_mut()
+            .expect("an entity should be added before adding to it")
+    }
+}
@@ -0,0 +1,147 @@
+use alloc::{string::String, vec::Vec};
+
+use serde_json::Value;
+
+use crate::{
+    resources::render::{MeshMaterial, StandardMaterialData},
+    EntityData, HashMap, ResourceData, SceneWorld, WorldEntity,
+};
+
+impl SceneWorld {
+    /// Merges resources with identical data, e.g. the same box shape exported under several
+    /// names, so that the loaders create a single collider shape or asset for all of them.
+    ///
+    /// Resources are compared by their type and serialized data. The first of the names in
+    /// sorted order is kept, and the references of entities and other resources are rewritten
+    /// to it. Merging textures can make materials identical, and those meshes in turn, so this
+    /// repeats until nothing is left to merge. Resources of unknown types are never merged,
+    /// as whatever refers to them does so through raw data that can't be rewritten.
+    ///
+    /// Returns the names of the dropped resources along with the name they were merged into,
+    /// to update references held outside of the world.
+    pub fn deduplicate_resources(&mut self) -> HashMap<String, String> {
+        let mut remap: HashMap<String, String> = HashMap::new();
+
+        loop {
+            let merged = self.find_duplicates();
+            if merged.is_empty() {
+                return remap;
+            }
+
+            for name in merged.keys() {
+                self.resources.remove(name);
+            }
+            for resource in self.resources.values_mut() {
+                rename_resource_references(&mut resource.data, &merged);
+            }
+            for entity in &mut self.entities {
+                rename_entity_references(entity, &merged);
+            }
+
+            // Names merged in earlier passes may point at a resource that was merged just now
+            for canonical in remap.values_mut() {
+                if let Some(name) = merged.get(canonical) {
+                    canonical.clone_from(name);
+                }
+            }
+            remap.extend(merged);
+        }
+    }
+
+    /// Maps every resource that has the same data as another one to the canonical name.
+    fn find_duplicates(&self) -> HashMap<String, String> {
+        let mut names: Vec<&String> = self.resources.keys().collect();
+        names.sort();
+
+        let mut canonical: HashMap<(&str, String), &String> = HashMap::new();
+        let mut merged = HashMap::new();
+        for name in names {
+            let data = &self.resources[name].data;
+            if let ResourceData::Unknown { .. } = data {
+                continue;
+            }
+
+            let content = serde_json::to_string(&data.to_value())
+                .expect("resource data should always serialize");
+
+            match canonical.get(&(data.type_name(), content.clone())) {
+                Some(first) => {
+                    merged.insert(name.clone(), (*first).clone());
+                }
+                None => {
+                    canonical.insert((data.type_name(), content), name);
+                }
+            }
+        }
+
+        merged
+    }
+}
+
+fn rename(name: &mut String, merged: &HashMap<String, String>) {
+    if let Some(canonical) = merged.get(name) {
+        name.clone_from(canonical);
+    }
+}
+
+fn rename_material_references(
+    material: &mut StandardMaterialData,
+    merged: &HashMap<String, String>,
+) {
+    let textures = [
+        &mut material.albedo_texture,
+        &mut material.normal_texture,
+        &mut material.metallic_texture,
+        &mut material.roughness_texture,
+    ];
+    for texture in textures.into_iter().flatten() {
+        rename(texture, merged);
+    }
+}
+
+fn rename_resource_references(resource: &mut ResourceData, merged: &HashMap<String, String>) {
+    let material = match resource {
+        ResourceData::BoxMesh(mesh) => &mut mesh.material,
+        ResourceData::SphereMesh(mesh) => &mut mesh.material,
+        ResourceData::PrismMesh(mesh) => &mut mesh.material,
+        ResourceData::TorusMesh(mesh) => &mut mesh.material,
+        ResourceData::StandardMaterial(material) => {
+            return rename_material_references(material, merged)
+        }
+        ResourceData::ORMMaterial(material) => {
+            if let Some(texture) = &mut material.orm_texture {
+                rename(texture, merged);
+            }
+            return rename_material_references(&mut material.material, merged);
+        }
+        _ => return,
+    };
+
+    match material {
+        Some(MeshMaterial::Resource(name)) => rename(name, merged),
+        Some(MeshMaterial::Inline(material)) => rename_material_references(material, merged),
+        None => {}
+    }
+}
+
+fn rename_entity_references(entity: &mut WorldEntity, merged: &HashMap<String, String>) {
+    match &mut entity.data {
+        EntityData::CollisionShape3D(shape) => rename(&mut shape.shape, merged),
+        EntityData::MeshInstance3D(instance) => rename(&mut instance.mesh, merged),
+        EntityData::GridMap(grid) => {
+            for name in grid.mesh_library.values_mut() {
+                rename(name, merged);
+            }
+        }
+        EntityData::ModelScene(scene) if scene.type_name == "MODEL" => {
+            if let Value::String(name) = &mut scene.data {
+                rename(name, merged);
+            }
+        }
+        _ => {}
+    }
+
+    for child in entity.children.iter_mut().flatten() {
+        rename_entity_references(child, merged);
+    }
+}
@@ -0,0 +1,60 @@
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+/// Godot `OmniLight3D`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OmniLightData {
+    pub transform: Vec<f32>,
+
+    /// RGBA, absent means white.
+    pub color: Option<Vec<f32>>,
+    pub energy: Option<f32>,
+    pub range: Option<f32>,
+
+    /// Godot's `omni_attenuation`, the exponent of the distance falloff.
+    pub attenuation: Option<f32>,
+
+    #[serde(rename = "shadowEnabled")]
+    pub shadow_enabled: Option<bool>,
+}
+
+/// Godot `SpotLight3D`, pointing along its -Z axis.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SpotLightData {
+    pub transform: Vec<f32>,
+
+    /// RGBA, absent means white.
+    pub color: Option<Vec<f32>>,
+    pub energy: Option<f32>,
+    pub range: Option<f32>,
+
+    /// Godot's `spot_attenuation`, the exponent of the distance falloff.
+    pub attenuation: Option<f32>,
+
+    /// Half of the cone angle, in degrees.
+    pub angle: Option<f32>,
+
+    /// Godot's `spot_angle_attenuation`, the exponent of the falloff towards the cone edge.
+    #[serde(rename = "angleAttenuation")]
+    pub angle_attenuation: Option<f32>,
+
+    #[serde(rename = "shadowEnabled")]
+    pub shadow_enabled: Option<bool>,
+}
+
+/// Godot `WorldEnvironment`, with the ambient light and background of its environment.
+/// It isn't a 3D node, so it has no transform of its own.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WorldEnvironmentData {
+    /// RGBA, absent when the environment has no custom ambient light.
+    #[serde(rename = "ambientColor")]
+    pub ambient_color: Option<Vec<f32>>,
+
+    #[serde(rename = "ambientEnergy")]
+    pub ambient_energy: Option<f32>,
+
+    /// RGBA, absent when the background isn't a plain color, e.g. a sky.
+    #[serde(rename = "backgroundColor")]
+    pub background_color: Option<Vec<f32>>,
+}
@@ -1,3 +1,4 @@
+pub mod light;
 pub mod node;
 pub mod physics;
 pub mod render;
@@ -1,11 +1,43 @@
+use alloc::{string::String, vec::Vec};
+
 use serde::{Deserialize, Serialize};
 
+use crate::HashMap;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Node3DData {
     pub transform: Vec<f32>,
+    pub visible: Option<bool>,
+
+    #[serde(rename = "processMode")]
+    pub process_mode: Option<ProcessMode>,
+
+    /// Other process related flags of the node, e.g. `physicsProcess`, passed on as is.
+    #[serde(rename = "processFlags")]
+    pub process_flags: Option<HashMap<String, bool>>,
+}
+
+/// Godot's `Node.process_mode`, deciding whether a node is processed while the tree is paused.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessMode {
+    #[default]
+    Inherit,
+    Pausable,
+    WhenPaused,
+    Always,
+    Disabled,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CameraData {
     pub transform: Vec<f32>,
 }
+
+/// Godot `AnimationPlayer`, kept for the names of its animations. It isn't a 3D node,
+/// so it has no transform of its own.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AnimationPlayerData {
+    pub animations: Vec<String>,
+    pub autoplay: Option<String>,
+}
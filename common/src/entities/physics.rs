@@ -1,30 +1,214 @@
+use alloc::{string::String, vec::Vec};
+
 use serde::{Deserialize, Serialize};
 
+use crate::{entities::node::ProcessMode, metadata::Metadata};
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CollisionShapeData {
     pub shape: String,
     pub transform: Vec<f32>,
+
+    /// Disabled shapes don't collide, so no collider is created for them.
+    pub disabled: Option<bool>,
+}
+
+impl CollisionShapeData {
+    pub fn is_disabled(&self) -> bool {
+        self.disabled == Some(true)
+    }
+}
+
+/// Surface and mass properties of a collider, from the `friction`, `restitution` and `density`
+/// metadata values. Absent values keep the defaults of the physics engine.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ColliderMaterial {
+    pub friction: Option<f32>,
+    pub restitution: Option<f32>,
+    pub density: Option<f32>,
+}
+
+impl ColliderMaterial {
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        let get = |key: &str| metadata.get(key).and_then(|x| x.as_f64()).map(|x| x as f32);
+
+        Self {
+            friction: get("friction"),
+            restitution: get("restitution"),
+            density: get("density"),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct StaticBodyData {
     pub transform: Vec<f32>,
+    pub visible: Option<bool>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct KinematicBodyData {
     pub transform: Vec<f32>,
+    pub visible: Option<bool>,
     #[serde(rename = "linearVelocity")]
-    pub linear_velocity: Option<Vec<f32>>,
+    pub linear_velocity: Option<[f32; 3]>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct RigidBodyData {
     pub transform: Vec<f32>,
+    pub visible: Option<bool>,
 
     #[serde(rename = "linearVelocity")]
-    pub linear_velocity: Option<Vec<f32>>,
+    pub linear_velocity: Option<[f32; 3]>,
 
     #[serde(rename = "angularVelocity")]
-    pub angular_velocity: Option<Vec<f32>>,
+    pub angular_velocity: Option<[f32; 3]>,
+
+    #[serde(rename = "gravityScale")]
+    pub gravity_scale: Option<f32>,
+
+    #[serde(rename = "linearDamp")]
+    pub linear_damp: Option<f32>,
+
+    #[serde(rename = "angularDamp")]
+    pub angular_damp: Option<f32>,
+
+    /// Locked degrees of freedom from Godot's `axis_lock_*` flags as a bitmask, see [axis_lock].
+    #[serde(rename = "axisLock")]
+    pub axis_lock: Option<u8>,
+
+    /// Godot's `freeze`. Frozen bodies are parked in the scene, so they start asleep.
+    pub freeze: Option<bool>,
+    pub sleeping: Option<bool>,
+
+    #[serde(rename = "canSleep")]
+    pub can_sleep: Option<bool>,
+
+    #[serde(rename = "processMode")]
+    pub process_mode: Option<ProcessMode>,
+}
+
+impl RigidBodyData {
+    /// State the body starts the simulation in, from its `freeze`, `sleeping`
+    /// and `process_mode`.
+    pub fn activation(&self) -> BodyActivation {
+        if self.process_mode == Some(ProcessMode::Disabled) {
+            BodyActivation::Disabled
+        } else if self.freeze == Some(true) || self.sleeping == Some(true) {
+            BodyActivation::Asleep
+        } else {
+            BodyActivation::Awake
+        }
+    }
+}
+
+/// State a rigid body starts the simulation in, see [RigidBodyData::activation].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyActivation {
+    #[default]
+    Awake,
+
+    /// Stays in place until it is touched, or given a velocity or a force, then it is
+    /// simulated like any other body. Bodies that can't sleep wake up right away.
+    Asleep,
+
+    /// Left out of the simulation along with its colliders, until it is enabled again.
+    Disabled,
+}
+
+/// Explicit mass properties of a rigid body, e.g. to lower the center of mass of a vehicle,
+/// from the `center_of_mass`, `mass` and `inertia` metadata values. They are added on top of
+/// the mass of the colliders, which can be taken out with a `density` of 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassOverride {
+    /// Defaults to 1, like the mass of a Godot body.
+    pub mass: f32,
+    pub center_of_mass: [f32; 3],
+
+    /// Principal angular inertia, defaults to none on top of the colliders.
+    pub principal_inertia: [f32; 3],
+}
+
+impl MassOverride {
+    /// Returns `None` without a `center_of_mass`, and the reason if a value is malformed.
+    pub fn from_metadata(metadata: &Metadata) -> Result<Option<Self>, &'static str> {
+        // `None` if the value is absent, `Some(None)` if it isn't an array of 3 numbers
+        let vector = |key: &str| -> Option<Option<[f32; 3]>> {
+            let value = metadata.get(key)?;
+            let values: Option<Vec<f32>> = value.as_array().and_then(|values| {
+                values
+                    .iter()
+                    .map(|x| x.as_f64().map(|x| x as f32))
+                    .collect()
+            });
+            Some(match values.as_deref() {
+                Some([x, y, z]) => Some([*x, *y, *z]),
+                _ => None,
+            })
+        };
+
+        let center_of_mass = match vector("center_of_mass") {
+            None => return Ok(None),
+            Some(None) => return Err("center_of_mass should be an array of 3 numbers"),
+            Some(Some(center)) => center,
+        };
+        let principal_inertia = match vector("inertia") {
+            None => [0.0; 3],
+            Some(None) => return Err("inertia should be an array of 3 numbers"),
+            Some(Some(inertia)) => inertia,
+        };
+        let mass = match metadata.get("mass") {
+            None => 1.0,
+            Some(mass) => mass.as_f64().ok_or("mass should be a number")? as f32,
+        };
+
+        Ok(Some(Self {
+            mass,
+            center_of_mass,
+            principal_inertia,
+        }))
+    }
+}
+
+/// Bits of [RigidBodyData::axis_lock], laid out like the locked axes of rapier.
+pub mod axis_lock {
+    pub const LINEAR_X: u8 = 1 << 0;
+    pub const LINEAR_Y: u8 = 1 << 1;
+    pub const LINEAR_Z: u8 = 1 << 2;
+    pub const ANGULAR_X: u8 = 1 << 3;
+    pub const ANGULAR_Y: u8 = 1 << 4;
+    pub const ANGULAR_Z: u8 = 1 << 5;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Area3DData {
+    pub transform: Vec<f32>,
+}
+
+/// Godot `PinJoint3D`, connecting two bodies at its origin.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PinJointData {
+    pub transform: Vec<f32>,
+
+    /// Names of the connected bodies, Godot leaves a side unset to pin a body in place.
+    #[serde(rename = "nodeA")]
+    pub node_a: Option<String>,
+    #[serde(rename = "nodeB")]
+    pub node_b: Option<String>,
+}
+
+/// Godot `HingeJoint3D`, connecting two bodies that rotate around its local Z axis.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct HingeJointData {
+    pub transform: Vec<f32>,
+
+    #[serde(rename = "nodeA")]
+    pub node_a: Option<String>,
+    #[serde(rename = "nodeB")]
+    pub node_b: Option<String>,
+
+    /// Lower and upper rotation limits in radians, if the limit is enabled.
+    #[serde(rename = "angularLimit")]
+    pub angular_limit: Option<[f32; 2]>,
 }
@@ -1,16 +1,123 @@
+use alloc::{string::String, vec::Vec};
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::HashMap;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct MeshInstanceData {
     pub mesh: String,
     pub visible: bool,
     pub transform: Vec<f32>,
+
+    /// Godot's `VisualInstance3D.layers` bitmask, where bit 0 is render layer 1.
+    #[serde(rename = "visibilityLayer")]
+    pub visibility_layer: Option<u32>,
+
+    /// Absent means the mesh casts shadows, like Godot's default.
+    #[serde(rename = "castShadow")]
+    pub cast_shadow: Option<ShadowCasting>,
+}
+
+/// Godot's `GeometryInstance3D.cast_shadow`, how a mesh takes part in shadows.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ShadowCasting {
+    Off,
+    #[default]
+    On,
+    DoubleSided,
+    /// The mesh itself is hidden, only its shadow is rendered.
+    ShadowsOnly,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Label3DData {
+    pub transform: Vec<f32>,
+    pub text: String,
+
+    #[serde(rename = "fontSize")]
+    pub font_size: f32,
+}
+
+/// Godot `Skeleton3D`, kept for its bones so that they can be mapped onto another skinning
+/// implementation.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SkeletonData {
+    pub transform: Vec<f32>,
+    pub bones: Vec<BoneData>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BoneData {
+    pub name: String,
+
+    /// Serialized rest transform of the bone, relative to its parent bone.
+    pub rest: Vec<f32>,
+}
+
+/// Godot `GridMap`, whose cells each place an item of its mesh library.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GridMapData {
+    pub transform: Vec<f32>,
+
+    /// Size of a cell, Godot's default is 2 units along every axis.
+    #[serde(rename = "cellSize")]
+    pub cell_size: Option<[f32; 3]>,
+    pub cells: Vec<GridCell>,
+
+    /// Mesh or collision shape resource placed by each item id.
+    #[serde(rename = "meshLibrary")]
+    pub mesh_library: HashMap<i32, String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GridCell {
+    pub position: [i32; 3],
+    pub item: i32,
+}
+
+impl GridMapData {
+    /// Cells along with the name of the resource they place. Cells whose item isn't in the
+    /// mesh library are left out.
+    pub fn items(&self) -> impl Iterator<Item = (&GridCell, &str)> {
+        self.cells.iter().filter_map(|cell| {
+            let resource = self.mesh_library.get(&cell.item)?;
+            Some((cell, resource.as_str()))
+        })
+    }
+
+    /// Column-major transform of a cell relative to the grid map, placing the item
+    /// at the center of the cell like Godot does by default.
+    pub fn cell_transform(&self, cell: &GridCell) -> [f32; 16] {
+        let size = self.cell_size.unwrap_or([2.0; 3]);
+        let center = |axis: usize| (cell.position[axis] as f32 + 0.5) * size[axis];
+
+        [
+            1.0,
+            0.0,
+            0.0,
+            0.0, //
+            0.0,
+            1.0,
+            0.0,
+            0.0, //
+            0.0,
+            0.0,
+            1.0,
+            0.0, //
+            center(0),
+            center(1),
+            center(2),
+            1.0,
+        ]
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ModelSceneData {
-    #[serde(rename(deserialize = "type"))]
+    #[serde(rename = "type")]
     pub type_name: String,
     pub data: Value,
     pub transform: Vec<f32>,
@@ -0,0 +1,58 @@
+use alloc::{borrow::ToOwned, string::String};
+
+/// A problem with the scene data that made a loader skip an entity, instead of aborting the load.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoaderError {
+    /// An entity references a resource that isn't in the resources map.
+    MissingResource { entity: String, resource: String },
+
+    /// A mesh references a material that isn't in the resources map.
+    MissingMaterial { mesh: String, material: String },
+
+    /// A resource can't be used for what it's referenced as, e.g. a material used as a mesh.
+    InvalidResource { resource: String, reason: String },
+
+    /// A joint can't connect its bodies, e.g. because one of them wasn't spawned.
+    InvalidJoint { joint: String, reason: String },
+
+    /// An entity of a known type has malformed data, so it was left out with its children.
+    InvalidEntity { entity: String, reason: String },
+}
+
+impl LoaderError {
+    /// Names the resource of an [LoaderError::InvalidResource], other errors are kept as is.
+    pub fn for_resource(self, name: &str) -> Self {
+        match self {
+            LoaderError::InvalidResource { reason, .. } => LoaderError::InvalidResource {
+                resource: name.to_owned(),
+                reason,
+            },
+            err => err,
+        }
+    }
+}
+
+impl core::fmt::Display for LoaderError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LoaderError::MissingResource { entity, resource } => write!(
+                f,
+                "entity {} references missing resource {}",
+                entity, resource
+            ),
+            LoaderError::MissingMaterial { mesh, material } => {
+                write!(f, "mesh {} references missing material {}", mesh, material)
+            }
+            LoaderError::InvalidResource { resource, reason } => {
+                write!(f, "resource {} {}", resource, reason)
+            }
+            LoaderError::InvalidJoint { joint, reason } => write!(f, "joint {} {}", joint, reason),
+            LoaderError::InvalidEntity { entity, reason } => {
+                write!(f, "entity {} {}", entity, reason)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LoaderError {}
@@ -0,0 +1,35 @@
+use alloc::vec::Vec;
+
+use crate::WorldEntity;
+
+/// Pre-order iterator over an entity tree, see [crate::SceneWorld::iter_entities].
+/// Yields every entity along with its depth (0 for root entities) and the name of its parent.
+pub struct EntityIter<'a> {
+    stack: Vec<(&'a WorldEntity, usize, Option<&'a str>)>,
+}
+
+impl<'a> EntityIter<'a> {
+    pub fn new(roots: &'a [WorldEntity]) -> Self {
+        Self {
+            stack: roots.iter().rev().map(|x| (x, 0, None)).collect(),
+        }
+    }
+}
+
+impl<'a> Iterator for EntityIter<'a> {
+    type Item = (&'a WorldEntity, usize, Option<&'a str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (entity, depth, parent) = self.stack.pop()?;
+
+        if let Some(children) = &entity.children {
+            // Pushed in reverse so that the first child is visited first
+            for child in children.iter().rev() {
+                self.stack
+                    .push((child, depth + 1, Some(entity.name.as_str())));
+            }
+        }
+
+        Some((entity, depth, parent))
+    }
+}
@@ -1,68 +1,175 @@
-use std::collections::HashMap;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
 
 use entities::{
-    node::{CameraData, Node3DData},
-    physics::{CollisionShapeData, KinematicBodyData, RigidBodyData, StaticBodyData},
-    render::{MeshInstanceData, ModelSceneData},
+    light::{OmniLightData, SpotLightData, WorldEnvironmentData},
+    node::{AnimationPlayerData, CameraData, Node3DData},
+    physics::{
+        Area3DData, CollisionShapeData, HingeJointData, KinematicBodyData, PinJointData,
+        RigidBodyData, StaticBodyData,
+    },
+    render::{GridMapData, Label3DData, MeshInstanceData, ModelSceneData, SkeletonData},
 };
+use error::LoaderError;
+use iter::EntityIter;
+use metadata::Metadata;
+use registry::ParserRegistry;
 use resources::{
-    physics::{BoxCollisionShapeData, ConcavePolygonCollisionShapeData, SphereCollisionShapeData},
+    physics::{
+        BoxCollisionShapeData, ConcavePolygonCollisionShapeData, HeightMapCollisionShapeData,
+        SphereCollisionShapeData, WorldBoundaryCollisionShapeData,
+    },
     render::{
-        ArrayMeshData, BoxMeshData, PackedSceneData, SphereMeshData, StandardMaterialData,
-        Texture2DData,
+        ArrayMeshData, BoxMeshData, ORMMaterialData, PackedSceneData, PrismMeshData,
+        ShaderMaterialData, SphereMeshData, StandardMaterialData, Texture2DData, TorusMeshData,
     },
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub mod bounds;
+pub mod builder;
+pub mod dedup;
 pub mod entities;
+pub mod error;
+pub mod iter;
 pub mod macros;
+pub mod metadata;
+pub mod plan;
+pub mod registry;
 pub mod resources;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod summary;
+pub mod transform;
+pub mod validate;
+
+/// Map used for the resources of a scene.
+#[cfg(feature = "std")]
+pub use std::collections::HashMap;
+
+/// Map used for the resources of a scene. Without the `std` feature this is hashbrown's map,
+/// which works with just `alloc`.
+#[cfg(not(feature = "std"))]
+pub use hashbrown::HashMap;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct WorldEntityJson {
     pub name: String,
-    #[serde(rename(deserialize = "type"))]
+    #[serde(rename = "type")]
     pub entity_type: String,
     pub data: Value,
-    pub metadata: HashMap<String, Value>,
+    pub metadata: Metadata,
     pub children: Option<Vec<WorldEntityJson>>,
 }
 
 macro_rules! serde_deser {
     ($var:expr) => {
-        serde_json::from_value($var.clone()).unwrap()
+        serde_json::from_value($var.clone())?
     };
 }
 
 impl WorldEntityJson {
-    pub fn parse_data(&self) -> EntityData {
-        return match self.entity_type.as_str() {
+    /// Parses the data of the entity, keeping unknown types as [EntityData::Unknown].
+    /// Returns an error if the entity is of a known type, but its data is malformed.
+    pub fn parse_data(&self) -> Result<EntityData, LoaderError> {
+        Ok(match self.try_parse_data()? {
+            Some(data) => data,
+            None => EntityData::Unknown {
+                entity_type: self.entity_type.clone(),
+                data: self.data.clone(),
+            },
+        })
+    }
+
+    /// Same as [WorldEntityJson::parse_data], but entity types registered in `parsers` are
+    /// parsed by them instead of the built-in parsers.
+    pub fn parse_data_with(&self, parsers: &ParserRegistry) -> Result<EntityData, LoaderError> {
+        match parsers.parse_entity(self) {
+            Some(data) => Ok(data),
+            None => self.parse_data(),
+        }
+    }
+
+    /// Same as [WorldEntityJson::parse_data_with], but returns `None` for unknown entity types.
+    pub fn try_parse_data_with(
+        &self,
+        parsers: &ParserRegistry,
+    ) -> Result<Option<EntityData>, LoaderError> {
+        match parsers.parse_entity(self) {
+            Some(data) => Ok(Some(data)),
+            None => self.try_parse_data(),
+        }
+    }
+
+    /// Same as [WorldEntityJson::parse_data], but returns `None` for unknown entity types.
+    pub fn try_parse_data(&self) -> Result<Option<EntityData>, LoaderError> {
+        self.parse_known_type()
+            .map_err(|err| LoaderError::InvalidEntity {
+                entity: self.name.clone(),
+                reason: format!("has malformed {} data: {}", self.entity_type, err),
+            })
+    }
+
+    fn parse_known_type(&self) -> Result<Option<EntityData>, serde_json::Error> {
+        Ok(Some(match self.entity_type.as_str() {
             "StaticBody3D" => EntityData::StaticBody3D(serde_deser!(self.data)),
             "MeshInstance3D" => EntityData::MeshInstance3D(serde_deser!(self.data)),
             "CollisionShape3D" => EntityData::CollisionShape3D(serde_deser!(self.data)),
             "Camera3D" => EntityData::Camera(serde_deser!(self.data)),
+            "OmniLight3D" => EntityData::OmniLight3D(serde_deser!(self.data)),
+            "SpotLight3D" => EntityData::SpotLight3D(serde_deser!(self.data)),
             "RigidBody3D" => EntityData::RigidBody3D(serde_deser!(self.data)),
             "Node3D" => EntityData::Node3D(serde_deser!(self.data)),
             "CharacterBody3D" => EntityData::KinematicBody3D(serde_deser!(self.data)),
-            "" => {
-                return EntityData::ModelScene(serde_deser!(self.data));
+            "Area3D" => EntityData::Area3D(serde_deser!(self.data)),
+            "PinJoint3D" => EntityData::PinJoint3D(serde_deser!(self.data)),
+            "HingeJoint3D" => EntityData::HingeJoint3D(serde_deser!(self.data)),
+            "Label3D" => EntityData::Label3D(serde_deser!(self.data)),
+            "Skeleton3D" => EntityData::Skeleton3D(serde_deser!(self.data)),
+            "GridMap" => EntityData::GridMap(serde_deser!(self.data)),
+            "AnimationPlayer" => EntityData::AnimationPlayer(serde_deser!(self.data)),
+            "WorldEnvironment" => EntityData::WorldEnvironment(serde_deser!(self.data)),
+            "" => EntityData::ModelScene(serde_deser!(self.data)),
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Parses the entity along with its children. Entities with malformed data are left out
+    /// along with their children, and the reasons are added to `errors`.
+    pub fn parse(&self, errors: &mut Vec<LoaderError>) -> Option<WorldEntity> {
+        self.parse_with(&ParserRegistry::default(), errors)
+    }
+
+    /// Same as [WorldEntityJson::parse], parsing the entity and its children with `parsers`.
+    pub fn parse_with(
+        &self,
+        parsers: &ParserRegistry,
+        errors: &mut Vec<LoaderError>,
+    ) -> Option<WorldEntity> {
+        let data = match self.parse_data_with(parsers) {
+            Ok(data) => data,
+            Err(err) => {
+                errors.push(err);
+                return None;
             }
-            _ => panic!("parsing invalid entity type {}", self.entity_type.as_str()),
         };
-    }
 
-    pub fn parse(&self) -> WorldEntity {
-        WorldEntity {
+        Some(WorldEntity {
             name: self.name.clone(),
             entity_type: self.entity_type.clone(),
-            data: self.parse_data(),
+            data,
             metadata: self.metadata.clone(),
-            children: self
-                .children
-                .clone()
-                .and_then(|x| Some(x.iter().map(|x| x.parse()).collect())),
-        }
+            children: self.children.as_ref().map(|children| {
+                children
+                    .iter()
+                    .filter_map(|x| x.parse_with(parsers, errors))
+                    .collect()
+            }),
+        })
     }
 }
 
@@ -71,43 +178,263 @@ pub struct WorldEntity {
     pub name: String,
     pub entity_type: String,
     pub data: EntityData,
-    pub metadata: HashMap<String, Value>,
+    pub metadata: Metadata,
     pub children: Option<Vec<WorldEntity>>,
 }
 
+impl WorldEntity {
+    /// Turns the entity back into its form in the scene file, see [SceneWorld::to_json].
+    pub fn to_json(&self) -> WorldEntityJson {
+        WorldEntityJson {
+            name: self.name.clone(),
+            entity_type: self.entity_type.clone(),
+            data: self.data.to_value(),
+            metadata: self.metadata.clone(),
+            children: self
+                .children
+                .as_ref()
+                .map(|children| children.iter().map(|x| x.to_json()).collect()),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum EntityData {
     StaticBody3D(StaticBodyData),
     RigidBody3D(RigidBodyData),
     KinematicBody3D(KinematicBodyData),
     CollisionShape3D(CollisionShapeData),
+    Area3D(Area3DData),
+    PinJoint3D(PinJointData),
+    HingeJoint3D(HingeJointData),
 
     ModelScene(ModelSceneData),
     MeshInstance3D(MeshInstanceData),
+    Label3D(Label3DData),
+    Skeleton3D(SkeletonData),
+    GridMap(GridMapData),
 
     Camera(CameraData),
+    OmniLight3D(OmniLightData),
+    SpotLight3D(SpotLightData),
     Node3D(Node3DData),
+    AnimationPlayer(AnimationPlayerData),
+    WorldEnvironment(WorldEnvironmentData),
+
+    /// Entity of a type that the crate doesn't know, e.g. from a newer Godot version,
+    /// with its raw data. The loaders place it at its parent, without a transform of its own.
+    Unknown {
+        entity_type: String,
+        data: Value,
+    },
+}
+
+impl EntityData {
+    /// Serialized Godot transform of the entity, relative to its parent.
+    /// Entities that aren't 3D nodes are placed at their parent.
+    pub fn transform(&self) -> &[f32] {
+        match self {
+            EntityData::StaticBody3D(data) => &data.transform,
+            EntityData::RigidBody3D(data) => &data.transform,
+            EntityData::KinematicBody3D(data) => &data.transform,
+            EntityData::CollisionShape3D(data) => &data.transform,
+            EntityData::Area3D(data) => &data.transform,
+            EntityData::PinJoint3D(data) => &data.transform,
+            EntityData::HingeJoint3D(data) => &data.transform,
+            EntityData::ModelScene(data) => &data.transform,
+            EntityData::MeshInstance3D(data) => &data.transform,
+            EntityData::Label3D(data) => &data.transform,
+            EntityData::Skeleton3D(data) => &data.transform,
+            EntityData::GridMap(data) => &data.transform,
+            EntityData::Camera(data) => &data.transform,
+            EntityData::OmniLight3D(data) => &data.transform,
+            EntityData::SpotLight3D(data) => &data.transform,
+            EntityData::Node3D(data) => &data.transform,
+            EntityData::AnimationPlayer(_)
+            | EntityData::WorldEnvironment(_)
+            | EntityData::Unknown { .. } => &transform::IDENTITY_TRANSFORM,
+        }
+    }
+
+    /// Godot type of the entity, as written in the `type` of the scene file.
+    /// Model scenes have an empty type.
+    pub fn type_name(&self) -> &str {
+        match self {
+            EntityData::StaticBody3D(_) => "StaticBody3D",
+            EntityData::RigidBody3D(_) => "RigidBody3D",
+            EntityData::KinematicBody3D(_) => "CharacterBody3D",
+            EntityData::CollisionShape3D(_) => "CollisionShape3D",
+            EntityData::Area3D(_) => "Area3D",
+            EntityData::PinJoint3D(_) => "PinJoint3D",
+            EntityData::HingeJoint3D(_) => "HingeJoint3D",
+            EntityData::ModelScene(_) => "",
+            EntityData::MeshInstance3D(_) => "MeshInstance3D",
+            EntityData::Label3D(_) => "Label3D",
+            EntityData::Skeleton3D(_) => "Skeleton3D",
+            EntityData::GridMap(_) => "GridMap",
+            EntityData::Camera(_) => "Camera3D",
+            EntityData::OmniLight3D(_) => "OmniLight3D",
+            EntityData::SpotLight3D(_) => "SpotLight3D",
+            EntityData::Node3D(_) => "Node3D",
+            EntityData::AnimationPlayer(_) => "AnimationPlayer",
+            EntityData::WorldEnvironment(_) => "WorldEnvironment",
+            EntityData::Unknown { entity_type, .. } => entity_type,
+        }
+    }
+
+    /// The `data` of the entity as it appears in the scene file.
+    pub fn to_value(&self) -> Value {
+        match self {
+            EntityData::StaticBody3D(data) => serde_json::to_value(data),
+            EntityData::RigidBody3D(data) => serde_json::to_value(data),
+            EntityData::KinematicBody3D(data) => serde_json::to_value(data),
+            EntityData::CollisionShape3D(data) => serde_json::to_value(data),
+            EntityData::Area3D(data) => serde_json::to_value(data),
+            EntityData::PinJoint3D(data) => serde_json::to_value(data),
+            EntityData::HingeJoint3D(data) => serde_json::to_value(data),
+            EntityData::ModelScene(data) => serde_json::to_value(data),
+            EntityData::MeshInstance3D(data) => serde_json::to_value(data),
+            EntityData::Label3D(data) => serde_json::to_value(data),
+            EntityData::Skeleton3D(data) => serde_json::to_value(data),
+            EntityData::GridMap(data) => serde_json::to_value(data),
+            EntityData::Camera(data) => serde_json::to_value(data),
+            EntityData::OmniLight3D(data) => serde_json::to_value(data),
+            EntityData::SpotLight3D(data) => serde_json::to_value(data),
+            EntityData::Node3D(data) => serde_json::to_value(data),
+            EntityData::AnimationPlayer(data) => serde_json::to_value(data),
+            EntityData::WorldEnvironment(data) => serde_json::to_value(data),
+            EntityData::Unknown { data, .. } => return data.clone(),
+        }
+        .expect("entity data should always serialize")
+    }
+
+    /// Whether the node is visible in Godot, if the entity type has a visibility.
+    /// Hidden nodes hide all of their children as well.
+    pub fn visible(&self) -> Option<bool> {
+        match self {
+            EntityData::StaticBody3D(data) => data.visible,
+            EntityData::RigidBody3D(data) => data.visible,
+            EntityData::KinematicBody3D(data) => data.visible,
+            EntityData::Node3D(data) => data.visible,
+            EntityData::MeshInstance3D(data) => Some(data.visible),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum ResourceData {
     BoxMesh(BoxMeshData),
     SphereMesh(SphereMeshData),
+    PrismMesh(PrismMeshData),
+    TorusMesh(TorusMeshData),
     ArrayMesh(ArrayMeshData),
 
     StandardMaterial(StandardMaterialData),
+    ORMMaterial(ORMMaterialData),
+    ShaderMaterial(ShaderMaterialData),
     Texture2D(Texture2DData),
 
     BoxCollisionShape(BoxCollisionShapeData),
     SphereCollisionShape(SphereCollisionShapeData),
     ConcavePolygonCollisionShape(ConcavePolygonCollisionShapeData),
+    HeightMapCollisionShape(HeightMapCollisionShapeData),
+    WorldBoundaryCollisionShape(WorldBoundaryCollisionShapeData),
 
     PackedScene(PackedSceneData),
+
+    /// Resource of a type that the crate doesn't know, with its raw data.
+    Unknown {
+        resource_type: String,
+        data: Value,
+    },
+}
+
+impl ResourceData {
+    /// Name of the Godot type that the resource was parsed from.
+    pub fn type_name(&self) -> &str {
+        match self {
+            ResourceData::BoxMesh(_) => "BoxMesh",
+            ResourceData::SphereMesh(_) => "SphereMesh",
+            ResourceData::PrismMesh(_) => "PrismMesh",
+            ResourceData::TorusMesh(_) => "TorusMesh",
+            ResourceData::ArrayMesh(_) => "ArrayMesh",
+            ResourceData::StandardMaterial(_) => "StandardMaterial3D",
+            ResourceData::ORMMaterial(_) => "ORMMaterial3D",
+            ResourceData::ShaderMaterial(_) => "ShaderMaterial",
+            ResourceData::Texture2D(_) => "Texture2D",
+            ResourceData::BoxCollisionShape(_) => "BoxShape3D",
+            ResourceData::SphereCollisionShape(_) => "SphereShape3D",
+            ResourceData::ConcavePolygonCollisionShape(_) => "ConcavePolygonShape3D",
+            ResourceData::HeightMapCollisionShape(_) => "HeightMapShape3D",
+            ResourceData::WorldBoundaryCollisionShape(_) => "WorldBoundaryShape3D",
+            ResourceData::PackedScene(_) => "PackedScene",
+            ResourceData::Unknown { resource_type, .. } => resource_type,
+        }
+    }
+
+    pub fn is_mesh(&self) -> bool {
+        matches!(
+            self,
+            ResourceData::BoxMesh(_)
+                | ResourceData::SphereMesh(_)
+                | ResourceData::PrismMesh(_)
+                | ResourceData::TorusMesh(_)
+                | ResourceData::ArrayMesh(_)
+        )
+    }
+
+    pub fn is_material(&self) -> bool {
+        matches!(
+            self,
+            ResourceData::StandardMaterial(_)
+                | ResourceData::ORMMaterial(_)
+                | ResourceData::ShaderMaterial(_)
+        )
+    }
+
+    pub fn is_collision_shape(&self) -> bool {
+        matches!(
+            self,
+            ResourceData::BoxCollisionShape(_)
+                | ResourceData::SphereCollisionShape(_)
+                | ResourceData::ConcavePolygonCollisionShape(_)
+                | ResourceData::HeightMapCollisionShape(_)
+                | ResourceData::WorldBoundaryCollisionShape(_)
+        )
+    }
+
+    /// The `data` of the resource as it appears in the scene file. External resources
+    /// are just their path.
+    pub fn to_value(&self) -> Value {
+        match self {
+            ResourceData::BoxMesh(data) => serde_json::to_value(data),
+            ResourceData::SphereMesh(data) => serde_json::to_value(data),
+            ResourceData::PrismMesh(data) => serde_json::to_value(data),
+            ResourceData::TorusMesh(data) => serde_json::to_value(data),
+            ResourceData::StandardMaterial(data) => serde_json::to_value(data),
+            ResourceData::ORMMaterial(data) => serde_json::to_value(data),
+            ResourceData::ShaderMaterial(data) => serde_json::to_value(data),
+            ResourceData::BoxCollisionShape(data) => serde_json::to_value(data),
+            ResourceData::SphereCollisionShape(data) => serde_json::to_value(data),
+            ResourceData::ConcavePolygonCollisionShape(data) => serde_json::to_value(data),
+            ResourceData::HeightMapCollisionShape(data) => serde_json::to_value(data),
+            ResourceData::WorldBoundaryCollisionShape(data) => serde_json::to_value(data),
+            ResourceData::ArrayMesh(data) => return Value::String(data.path.clone()),
+            ResourceData::Texture2D(data) => return Value::String(data.path.clone()),
+            ResourceData::PackedScene(data) if data.scene_index.is_none() => {
+                return Value::String(data.path.clone())
+            }
+            ResourceData::PackedScene(data) => serde_json::to_value(data),
+            ResourceData::Unknown { data, .. } => return data.clone(),
+        }
+        .expect("resource data should always serialize")
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct WorldResourceJson {
-    #[serde(rename(deserialize = "type"))]
+    #[serde(rename = "type")]
     resource_type: String,
     data: Value,
 }
@@ -117,56 +444,172 @@ pub struct WorldResource {
     pub data: ResourceData,
 }
 
+impl WorldResource {
+    /// Turns the resource back into its form in the scene file, see [SceneWorld::to_json].
+    pub fn to_json(&self) -> WorldResourceJson {
+        WorldResourceJson {
+            resource_type: self.data.type_name().to_owned(),
+            data: self.data.to_value(),
+        }
+    }
+}
+
+/// Data of one resource type, see [SceneWorld::resources_of_type].
+pub trait ResourceType {
+    /// The data of `resource`, or `None` if it is of another type.
+    fn from_resource(resource: &ResourceData) -> Option<&Self>;
+}
+
+/// Generates accessors on [WorldResource] returning the data of one resource type,
+/// or `None` if the resource is of another type, and the matching [ResourceType] impls.
+macro_rules! resource_accessors {
+    ($($name:ident => $variant:ident($data:ty)),* $(,)?) => {
+        impl WorldResource {
+            $(
+                #[doc = concat!("The data of a `", stringify!($variant), "` resource.")]
+                pub fn $name(&self) -> Option<&$data> {
+                    <$data>::from_resource(&self.data)
+                }
+            )*
+        }
+
+        $(
+            impl ResourceType for $data {
+                fn from_resource(resource: &ResourceData) -> Option<&Self> {
+                    match resource {
+                        ResourceData::$variant(data) => Some(data),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+resource_accessors! {
+    as_box_mesh => BoxMesh(BoxMeshData),
+    as_sphere_mesh => SphereMesh(SphereMeshData),
+    as_prism_mesh => PrismMesh(PrismMeshData),
+    as_torus_mesh => TorusMesh(TorusMeshData),
+    as_array_mesh => ArrayMesh(ArrayMeshData),
+    as_material => StandardMaterial(StandardMaterialData),
+    as_orm_material => ORMMaterial(ORMMaterialData),
+    as_shader_material => ShaderMaterial(ShaderMaterialData),
+    as_texture => Texture2D(Texture2DData),
+    as_box_shape => BoxCollisionShape(BoxCollisionShapeData),
+    as_sphere_shape => SphereCollisionShape(SphereCollisionShapeData),
+    as_concave_polygon_shape => ConcavePolygonCollisionShape(ConcavePolygonCollisionShapeData),
+    as_height_map_shape => HeightMapCollisionShape(HeightMapCollisionShapeData),
+    as_world_boundary_shape => WorldBoundaryCollisionShape(WorldBoundaryCollisionShapeData),
+    as_packed_scene => PackedScene(PackedSceneData),
+}
+
 impl WorldResourceJson {
-    pub fn parse(&self) -> WorldResource {
-        return WorldResource {
-            data: self.parse_data(),
-        };
+    /// Name of the Godot type of the resource.
+    pub fn resource_type(&self) -> &str {
+        &self.resource_type
+    }
+
+    /// Raw data of the resource, for custom parsers.
+    pub fn data(&self) -> &Value {
+        &self.data
+    }
+
+    /// Parses the resource, keeping unknown types as [ResourceData::Unknown]. Returns an error
+    /// if the resource is of a known type, but its data is malformed.
+    pub fn parse(&self) -> Result<WorldResource, LoaderError> {
+        self.parse_with(&ParserRegistry::default())
+    }
+
+    /// Same as [WorldResourceJson::parse], parsing the resource with `parsers`.
+    pub fn parse_with(&self, parsers: &ParserRegistry) -> Result<WorldResource, LoaderError> {
+        Ok(WorldResource {
+            data: self.parse_data_with(parsers)?,
+        })
+    }
+
+    /// Same as [WorldResourceJson::parse_data], but resource types registered in `parsers`
+    /// are parsed by them instead of the built-in parsers.
+    pub fn parse_data_with(&self, parsers: &ParserRegistry) -> Result<ResourceData, LoaderError> {
+        match parsers.parse_resource(self) {
+            Some(data) => Ok(data),
+            None => self.parse_data(),
+        }
     }
 
-    pub fn parse_data(&self) -> ResourceData {
-        return match self.resource_type.as_str() {
+    /// Same as [WorldResourceJson::parse_data_with], but returns `None` for unknown resource
+    /// types.
+    pub fn try_parse_data_with(
+        &self,
+        parsers: &ParserRegistry,
+    ) -> Result<Option<ResourceData>, LoaderError> {
+        match parsers.parse_resource(self) {
+            Some(data) => Ok(Some(data)),
+            None => self.try_parse_data(),
+        }
+    }
+
+    /// Parses the data of the resource, keeping unknown types as [ResourceData::Unknown].
+    pub fn parse_data(&self) -> Result<ResourceData, LoaderError> {
+        Ok(match self.try_parse_data()? {
+            Some(data) => data,
+            None => ResourceData::Unknown {
+                resource_type: self.resource_type.clone(),
+                data: self.data.clone(),
+            },
+        })
+    }
+
+    /// Same as [WorldResourceJson::parse_data], but returns `None` for unknown resource types.
+    ///
+    /// The resource doesn't know its own name, so the error names it by its type until
+    /// [LoaderError::for_resource] is called.
+    pub fn try_parse_data(&self) -> Result<Option<ResourceData>, LoaderError> {
+        self.parse_known_type()
+            .map_err(|err| LoaderError::InvalidResource {
+                resource: self.resource_type.clone(),
+                reason: format!("has malformed {} data: {}", self.resource_type, err),
+            })
+    }
+
+    fn parse_known_type(&self) -> Result<Option<ResourceData>, serde_json::Error> {
+        Ok(Some(match self.resource_type.as_str() {
             "BoxMesh" => ResourceData::BoxMesh(serde_deser!(self.data)),
             "SphereMesh" => ResourceData::SphereMesh(serde_deser!(self.data)),
+            "PrismMesh" => ResourceData::PrismMesh(serde_deser!(self.data)),
+            "TorusMesh" => ResourceData::TorusMesh(serde_deser!(self.data)),
             "StandardMaterial3D" => ResourceData::StandardMaterial(serde_deser!(self.data)),
+            "ORMMaterial3D" => ResourceData::ORMMaterial(serde_deser!(self.data)),
+            "ShaderMaterial" => ResourceData::ShaderMaterial(serde_deser!(self.data)),
             "ConcavePolygonShape3D" => {
                 ResourceData::ConcavePolygonCollisionShape(serde_deser!(self.data))
             }
             "BoxShape3D" => ResourceData::BoxCollisionShape(serde_deser!(self.data)),
             "SphereShape3D" => ResourceData::SphereCollisionShape(serde_deser!(self.data)),
+            "HeightMapShape3D" => ResourceData::HeightMapCollisionShape(serde_deser!(self.data)),
+            "WorldBoundaryShape3D" => {
+                ResourceData::WorldBoundaryCollisionShape(serde_deser!(self.data))
+            }
             "ArrayMesh" => {
-                let path = self
-                    .data
-                    .as_str()
-                    .expect("ExtResource data should always be string");
+                let path: String = serde_deser!(self.data);
 
-                ResourceData::ArrayMesh(ArrayMeshData {
-                    path: path.to_owned(),
-                })
+                ResourceData::ArrayMesh(ArrayMeshData { path })
             }
             "Texture2D" => {
-                let path = self
-                    .data
-                    .as_str()
-                    .expect("ExtResource data should always be string");
+                let path: String = serde_deser!(self.data);
 
-                ResourceData::Texture2D(Texture2DData {
-                    path: path.to_owned(),
-                })
+                ResourceData::Texture2D(Texture2DData { path })
             }
 
-            "PackedScene" => {
-                let path = self
-                    .data
-                    .as_str()
-                    .expect("ExtResource data should always be string");
-
-                ResourceData::PackedScene(PackedSceneData {
+            "PackedScene" => match self.data.as_str() {
+                Some(path) => ResourceData::PackedScene(PackedSceneData {
                     path: path.to_owned(),
-                })
-            }
-            _ => panic!("invalid resource type {}", self.resource_type.as_str()),
-        };
+                    scene_index: None,
+                }),
+                None => ResourceData::PackedScene(serde_deser!(self.data)),
+            },
+            _ => return Ok(None),
+        }))
     }
 }
 
@@ -177,15 +620,38 @@ pub struct SceneWorldJson {
 }
 
 impl SceneWorldJson {
+    /// Parses the entities and resources. Those with malformed data are left out, instead of
+    /// failing the whole scene, and reported in [SceneWorld::errors].
     pub fn to_world(&self) -> SceneWorld {
-        return SceneWorld {
-            entities: self.entities.iter().map(|x| x.parse()).collect(),
-            resources: self
-                .resources
-                .iter()
-                .map(|(k, v)| (k.clone(), v.parse()))
-                .collect(),
-        };
+        self.to_world_with(&ParserRegistry::default())
+    }
+
+    /// Same as [SceneWorldJson::to_world], but entities and resources of the types registered
+    /// in `parsers` are parsed by them, instead of being kept as raw data.
+    pub fn to_world_with(&self, parsers: &ParserRegistry) -> SceneWorld {
+        let mut errors = Vec::new();
+
+        let mut resources = HashMap::new();
+        for (name, resource) in &self.resources {
+            match resource.parse_with(parsers) {
+                Ok(resource) => {
+                    resources.insert(name.clone(), resource);
+                }
+                Err(err) => errors.push(err.for_resource(name)),
+            }
+        }
+
+        let entities = self
+            .entities
+            .iter()
+            .filter_map(|x| x.parse_with(parsers, &mut errors))
+            .collect();
+
+        SceneWorld {
+            entities,
+            resources,
+            errors,
+        }
     }
 }
 
@@ -193,11 +659,161 @@ impl SceneWorldJson {
 pub struct SceneWorld {
     pub entities: Vec<WorldEntity>,
     pub resources: HashMap<String, WorldResource>,
+
+    /// Entities and resources that were left out when parsing the scene, because their data
+    /// is malformed. The loaders return them along with their own errors.
+    #[serde(skip)]
+    pub errors: Vec<LoaderError>,
+}
+
+impl SceneWorld {
+    /// Walks the whole entity tree in pre-order, see [EntityIter].
+    pub fn iter_entities(&self) -> EntityIter<'_> {
+        EntityIter::new(&self.entities)
+    }
+
+    /// Turns the world back into the structure of the scene file, with the `type` tags and raw
+    /// `data` of every entity and resource, so that a modified world can be saved and loaded
+    /// again. Serializing the [SceneWorld] itself produces a different structure that
+    /// the loaders can't read.
+    pub fn to_json(&self) -> SceneWorldJson {
+        SceneWorldJson {
+            entities: self.entities.iter().map(|x| x.to_json()).collect(),
+            resources: self
+                .resources
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_json()))
+                .collect(),
+        }
+    }
+
+    /// Names and data of all resources of one type, in no particular order,
+    /// e.g. `world.resources_of_type::<Texture2DData>()`.
+    pub fn resources_of_type<'a, T: ResourceType + 'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (&'a str, &'a T)> {
+        self.resources
+            .iter()
+            .filter_map(|(name, resource)| Some((name.as_str(), T::from_resource(&resource.data)?)))
+    }
+
+    /// All textures, e.g. to load them before spawning the scene.
+    pub fn textures(&self) -> impl Iterator<Item = (&str, &Texture2DData)> {
+        self.resources_of_type()
+    }
+
+    /// All meshes, of any of the mesh types.
+    pub fn meshes(&self) -> impl Iterator<Item = (&str, &ResourceData)> {
+        self.resources_matching(ResourceData::is_mesh)
+    }
+
+    /// All materials, of any of the material types.
+    pub fn materials(&self) -> impl Iterator<Item = (&str, &ResourceData)> {
+        self.resources_matching(ResourceData::is_material)
+    }
+
+    /// All collision shapes, of any of the shape types.
+    pub fn collision_shapes(&self) -> impl Iterator<Item = (&str, &ResourceData)> {
+        self.resources_matching(ResourceData::is_collision_shape)
+    }
+
+    fn resources_matching(
+        &self,
+        predicate: fn(&ResourceData) -> bool,
+    ) -> impl Iterator<Item = (&str, &ResourceData)> {
+        self.resources
+            .iter()
+            .map(|(name, resource)| (name.as_str(), &resource.data))
+            .filter(move |(_, data)| predicate(data))
+    }
+
+    /// Finds the first entity with the given name anywhere in the tree.
+    pub fn find_entity(&self, name: &str) -> Option<&WorldEntity> {
+        self.iter_entities()
+            .map(|(entity, _, _)| entity)
+            .find(|entity| entity.name == name)
+    }
+}
+
+/// Failure to read a scene file, see [try_load_scene_world_file].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum SceneFileError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    #[cfg(feature = "zip")]
+    Archive(zip::result::ZipError),
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneFileError::Io(err) => write!(f, "unable to read scene file: {}", err),
+            SceneFileError::Json(err) => write!(f, "scene file is not proper JSON: {}", err),
+            #[cfg(feature = "zip")]
+            SceneFileError::Archive(err) => write!(f, "unable to read scene archive: {}", err),
+        }
+    }
 }
 
-pub fn load_scene_world_file(file: &str) -> SceneWorld {
-    let file = std::fs::File::open(file).unwrap();
-    let json: SceneWorldJson = serde_json::from_reader(file).expect("file should be proper JSON");
+#[cfg(feature = "std")]
+impl std::error::Error for SceneFileError {}
+
+/// Same as [load_scene_world_file], for any kind of path.
+#[cfg(feature = "std")]
+pub fn try_load_scene_world_file(
+    file: impl AsRef<std::path::Path>,
+) -> Result<SceneWorld, SceneFileError> {
+    let file = std::fs::File::open(file).map_err(SceneFileError::Io)?;
+    try_load_scene_world_reader(std::io::BufReader::new(file))
+}
+
+/// Parses a scene from any reader, e.g. a scene embedded in the binary or received over the
+/// network. The reader isn't buffered, so wrap it in a [std::io::BufReader] where that matters.
+#[cfg(feature = "std")]
+pub fn try_load_scene_world_reader(
+    reader: impl std::io::Read,
+) -> Result<SceneWorld, SceneFileError> {
+    let json: SceneWorldJson = serde_json::from_reader(reader).map_err(SceneFileError::Json)?;
+
+    Ok(json.to_world())
+}
+
+/// Reads the scene stored as `entry` inside a zip archive, without extracting it to disk.
+/// The entry may be given as a Godot `res://` path.
+#[cfg(feature = "zip")]
+pub fn load_scene_world_from_archive(
+    archive: impl AsRef<std::path::Path>,
+    entry: &str,
+) -> Result<SceneWorld, SceneFileError> {
+    let file = std::fs::File::open(archive).map_err(SceneFileError::Io)?;
+    let mut archive =
+        zip::ZipArchive::new(std::io::BufReader::new(file)).map_err(SceneFileError::Archive)?;
+    let entry = archive
+        .by_name(entry.trim_start_matches("res://"))
+        .map_err(SceneFileError::Archive)?;
+
+    try_load_scene_world_reader(std::io::BufReader::new(entry))
+}
+
+/// Loads a scene file on tokio's blocking thread pool, so that large scenes don't stall
+/// the async runtime while being read and parsed.
+#[cfg(feature = "tokio")]
+pub async fn load_scene_world_file_async(
+    file: impl AsRef<std::path::Path>,
+) -> Result<SceneWorld, SceneFileError> {
+    let file = file.as_ref().to_owned();
+    match tokio::task::spawn_blocking(move || try_load_scene_world_file(file)).await {
+        Ok(result) => result,
+        // Blocking tasks can't be cancelled, so the task can only have failed by panicking
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
 
-    json.to_world()
+/// Reads and parses a scene file. Entities and resources with malformed data are left out,
+/// see [SceneWorld::errors].
+#[cfg(feature = "std")]
+pub fn load_scene_world_file(file: &str) -> Result<SceneWorld, SceneFileError> {
+    try_load_scene_world_file(file)
 }
@@ -0,0 +1,115 @@
+use alloc::{string::String, vec::Vec};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(feature = "std")]
+type MetaHasher = std::collections::hash_map::RandomState;
+#[cfg(not(feature = "std"))]
+type MetaHasher = hashbrown::hash_map::DefaultHashBuilder;
+
+/// Map of metadata entries, which keeps the order of the scene file so that saving the scene
+/// again doesn't shuffle them.
+pub type MetaMap<V> = IndexMap<String, V, MetaHasher>;
+
+/// Compact typed representation of a Godot metadata value, used in place of
+/// [serde_json::Value] with the `typed_metadata` feature.
+/// Mirrors the accessors of [serde_json::Value], so code reading metadata works with both.
+/// Integers are kept apart from other numbers, so that they are saved as integers again.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(untagged)]
+pub enum MetaValue {
+    #[default]
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Number(f64),
+    String(String),
+    Array(Vec<MetaValue>),
+    Object(MetaMap<MetaValue>),
+}
+
+impl MetaValue {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            MetaValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MetaValue::Integer(value) => Some(*value as f64),
+            MetaValue::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The number as an integer, if it has no fractional part.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            MetaValue::Integer(value) => Some(*value),
+            MetaValue::Number(value) if value % 1.0 == 0.0 => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MetaValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<MetaValue>> {
+        match self {
+            MetaValue::Array(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&MetaMap<MetaValue>> {
+        match self {
+            MetaValue::Object(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, MetaValue::Null)
+    }
+}
+
+impl From<Value> for MetaValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => MetaValue::Null,
+            Value::Bool(value) => MetaValue::Bool(value),
+            Value::Number(value) => match value.as_i64() {
+                Some(value) => MetaValue::Integer(value),
+                // Numbers that don't fit an f64 can't come from Godot anyway
+                None => MetaValue::Number(value.as_f64().unwrap_or_default()),
+            },
+            Value::String(value) => MetaValue::String(value),
+            Value::Array(values) => MetaValue::Array(values.into_iter().map(Into::into).collect()),
+            Value::Object(values) => MetaValue::Object(
+                values
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Value of a single metadata entry of an entity.
+#[cfg(feature = "typed_metadata")]
+pub type MetadataValue = MetaValue;
+
+/// Value of a single metadata entry of an entity.
+#[cfg(not(feature = "typed_metadata"))]
+pub type MetadataValue = Value;
+
+/// Metadata of a Godot node, keyed by name.
+pub type Metadata = MetaMap<MetadataValue>;
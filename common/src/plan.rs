@@ -0,0 +1,288 @@
+use alloc::{borrow::ToOwned, format, string::String, vec, vec::Vec};
+
+use crate::{
+    entities::{
+        light::{OmniLightData, SpotLightData, WorldEnvironmentData},
+        node::{AnimationPlayerData, Node3DData},
+        physics::{CollisionShapeData, KinematicBodyData, RigidBodyData},
+        render::{GridMapData, Label3DData, MeshInstanceData, SkeletonData},
+    },
+    error::LoaderError,
+    resources::render::{MeshMaterial, PackedSceneData},
+    transform::{mul_matrix_columns, to_matrix_columns, TransformError},
+    EntityData, HashMap, ResourceData, SceneWorld, WorldEntity, WorldResource,
+};
+
+/// What the loaders spawn for an entity, decided from the scene data alone.
+/// References to resources are resolved, so that executing the plan can't fail.
+#[derive(Debug, Clone, Copy)]
+pub enum SpawnPlan<'a> {
+    StaticBody,
+    RigidBody(&'a RigidBodyData),
+    KinematicBody(&'a KinematicBodyData),
+
+    /// A collision shape along with its shape resource. Disabled shapes are planned as well,
+    /// so that their children still get spawned.
+    Collider {
+        shape: &'a CollisionShapeData,
+        resource: &'a ResourceData,
+        sensor: bool,
+    },
+    Area,
+
+    /// A joint, which the rapier loader connects once all bodies are spawned.
+    Joint,
+
+    /// A mesh instance along with its mesh resource.
+    Mesh {
+        instance: &'a MeshInstanceData,
+        resource: &'a ResourceData,
+    },
+
+    /// A grid map, whose mesh library only refers to meshes and collision shapes.
+    GridMap(&'a GridMapData),
+
+    /// An instance of a packed scene, e.g. an imported glTF model.
+    Scene(&'a PackedSceneData),
+    Label(&'a Label3DData),
+    Skeleton(&'a SkeletonData),
+    Camera,
+    OmniLight(&'a OmniLightData),
+    SpotLight(&'a SpotLightData),
+    Node(&'a Node3DData),
+    AnimationPlayer(&'a AnimationPlayerData),
+
+    /// The environment of the scene, which the loaders apply globally rather than spawning it.
+    Environment(&'a WorldEnvironmentData),
+
+    /// An entity of an unknown type, see [EntityData::Unknown].
+    Unknown,
+
+    /// Nothing is spawned, but the children of the entity still are.
+    Placeholder,
+}
+
+/// Decides what to spawn for `entity`. `in_area` tells whether the parent is an `Area3D`,
+/// whose collision shapes are always sensors.
+pub fn plan_entity<'a>(
+    entity: &'a WorldEntity,
+    in_area: bool,
+    resources: &'a HashMap<String, WorldResource>,
+) -> Result<SpawnPlan<'a>, LoaderError> {
+    Ok(match &entity.data {
+        EntityData::StaticBody3D(_) => SpawnPlan::StaticBody,
+        EntityData::RigidBody3D(body) => SpawnPlan::RigidBody(body),
+        EntityData::KinematicBody3D(body) => SpawnPlan::KinematicBody(body),
+        EntityData::CollisionShape3D(shape) => {
+            let resource = resolve(entity, &shape.shape, resources)?;
+            if !resource.is_collision_shape() {
+                return Err(invalid(&shape.shape, "is not a collision shape".to_owned()));
+            }
+            check_collision_shape(&shape.shape, resource)?;
+
+            let sensor =
+                in_area || entity.metadata.get("sensor").and_then(|x| x.as_bool()) == Some(true);
+            SpawnPlan::Collider {
+                shape,
+                resource,
+                sensor,
+            }
+        }
+        EntityData::Area3D(_) => SpawnPlan::Area,
+        EntityData::PinJoint3D(_) | EntityData::HingeJoint3D(_) => SpawnPlan::Joint,
+        EntityData::MeshInstance3D(instance) => {
+            let resource = resolve(entity, &instance.mesh, resources)?;
+            check_mesh(&instance.mesh, resource, resources)?;
+            SpawnPlan::Mesh { instance, resource }
+        }
+        EntityData::ModelScene(scene) => match (scene.type_name.as_str(), scene.data.as_str()) {
+            ("MODEL", Some(path)) => match resolve(entity, path, resources)? {
+                ResourceData::PackedScene(packed) => SpawnPlan::Scene(packed),
+                _ => return Err(invalid(path, "is not a packed scene".to_owned())),
+            },
+            _ => SpawnPlan::Placeholder,
+        },
+        EntityData::GridMap(grid) => {
+            for name in grid.mesh_library.values() {
+                let resource = resolve(entity, name, resources)?;
+                if resource.is_collision_shape() {
+                    check_collision_shape(name, resource)?;
+                } else if resource.is_mesh() {
+                    check_mesh(name, resource, resources)?;
+                } else {
+                    let reason = "is not a mesh or collision shape".to_owned();
+                    return Err(invalid(name, reason));
+                }
+            }
+
+            SpawnPlan::GridMap(grid)
+        }
+        EntityData::Label3D(label) => SpawnPlan::Label(label),
+        EntityData::Skeleton3D(skeleton) => SpawnPlan::Skeleton(skeleton),
+        EntityData::Camera(_) => SpawnPlan::Camera,
+        EntityData::OmniLight3D(light) => SpawnPlan::OmniLight(light),
+        EntityData::SpotLight3D(light) => SpawnPlan::SpotLight(light),
+        EntityData::Node3D(node) => SpawnPlan::Node(node),
+        EntityData::AnimationPlayer(player) => SpawnPlan::AnimationPlayer(player),
+        EntityData::WorldEnvironment(environment) => SpawnPlan::Environment(environment),
+        EntityData::Unknown { .. } => SpawnPlan::Unknown,
+    })
+}
+
+fn invalid(name: &str, reason: String) -> LoaderError {
+    LoaderError::InvalidResource {
+        resource: name.to_owned(),
+        reason,
+    }
+}
+
+/// Rejects mesh resources named `name` that aren't meshes, or whose material is missing,
+/// isn't a material or has colors that can't be read.
+pub fn check_mesh(
+    name: &str,
+    resource: &ResourceData,
+    resources: &HashMap<String, WorldResource>,
+) -> Result<(), LoaderError> {
+    let material = match resource {
+        ResourceData::BoxMesh(mesh) => mesh.material.as_ref(),
+        ResourceData::SphereMesh(mesh) => mesh.material.as_ref(),
+        ResourceData::PrismMesh(mesh) => mesh.material.as_ref(),
+        ResourceData::TorusMesh(mesh) => mesh.material.as_ref(),
+        ResourceData::ArrayMesh(_) => None,
+        _ => return Err(invalid(name, "is not a mesh".to_owned())),
+    };
+
+    let colors = "has a color that isn't RGB or RGBA".to_owned();
+    match material {
+        Some(MeshMaterial::Resource(material)) => {
+            let resource = resources
+                .get(material)
+                .ok_or_else(|| LoaderError::MissingMaterial {
+                    mesh: name.to_owned(),
+                    material: material.clone(),
+                })?;
+            let valid = match &resource.data {
+                ResourceData::StandardMaterial(data) => data.is_valid(),
+                ResourceData::ORMMaterial(data) => data.material.is_valid(),
+                ResourceData::ShaderMaterial(data) => data.is_valid(),
+                _ => return Err(invalid(material, "is not a material".to_owned())),
+            };
+            if !valid {
+                return Err(invalid(material, colors));
+            }
+        }
+        Some(MeshMaterial::Inline(material)) if !material.is_valid() => {
+            return Err(invalid(name, colors));
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Rejects collision shape resources whose data can't make a valid collider.
+fn check_collision_shape(name: &str, resource: &ResourceData) -> Result<(), LoaderError> {
+    let reason = match resource {
+        ResourceData::ConcavePolygonCollisionShape(shape) if shape.data.len() % 3 != 0 => {
+            format!(
+                "has {} values, which aren't whole vertices of 3",
+                shape.data.len()
+            )
+        }
+        ResourceData::HeightMapCollisionShape(height_map) if !height_map.is_valid() => format!(
+            "has a {}x{} grid that doesn't match its {} samples",
+            height_map.width,
+            height_map.depth,
+            height_map.data.len()
+        ),
+        ResourceData::WorldBoundaryCollisionShape(boundary)
+            if boundary.normal.iter().all(|x| *x == 0.0) =>
+        {
+            "has a zero normal".to_owned()
+        }
+        _ => return Ok(()),
+    };
+
+    Err(invalid(name, reason))
+}
+
+fn resolve<'a>(
+    entity: &WorldEntity,
+    name: &str,
+    resources: &'a HashMap<String, WorldResource>,
+) -> Result<&'a ResourceData, LoaderError> {
+    resources
+        .get(name)
+        .map(|resource| &resource.data)
+        .ok_or_else(|| LoaderError::MissingResource {
+            entity: entity.name.clone(),
+            resource: name.to_owned(),
+        })
+}
+
+/// Report of what would be spawned for an entity, see [plan_scene].
+#[derive(Debug, Clone)]
+pub struct SpawnPlanEntry<'a> {
+    pub entity: &'a WorldEntity,
+
+    /// Name of the parent entity in the scene, if any.
+    pub parent: Option<&'a str>,
+
+    /// Column-major world transform of the entity. Fails if the transform of the entity,
+    /// or of one of its ancestors, is malformed.
+    pub transform: Result<[f32; 16], TransformError>,
+    pub plan: Result<SpawnPlan<'a>, LoaderError>,
+}
+
+/// Runs the spawn decisions of the loaders over the whole scene without spawning anything,
+/// e.g. to debug why an entity doesn't show up. Entries are in pre-order, like
+/// [SceneWorld::iter_entities].
+pub fn plan_scene(world: &SceneWorld) -> Vec<SpawnPlanEntry<'_>> {
+    let mut entries = vec![];
+
+    for entity in &world.entities {
+        plan_subtree(world, entity, None, &Ok(IDENTITY), &mut entries);
+    }
+
+    entries
+}
+
+const IDENTITY: [f32; 16] = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+fn plan_subtree<'a>(
+    world: &'a SceneWorld,
+    entity: &'a WorldEntity,
+    parent: Option<&'a WorldEntity>,
+    parent_transform: &Result<[f32; 16], TransformError>,
+    entries: &mut Vec<SpawnPlanEntry<'a>>,
+) {
+    let transform = parent_transform.clone().and_then(|parent| {
+        let relative = to_matrix_columns(entity.data.transform())
+            .map_err(|err| err.for_entity(&entity.name))?;
+        Ok(mul_matrix_columns(&parent, &relative))
+    });
+
+    let in_area = matches!(parent.map(|x| &x.data), Some(EntityData::Area3D(_)));
+    entries.push(SpawnPlanEntry {
+        entity,
+        parent: parent.map(|x| x.name.as_str()),
+        transform: transform.clone(),
+        plan: plan_entity(entity, in_area, &world.resources),
+    });
+
+    for child in entity.children.iter().flatten() {
+        plan_subtree(world, child, Some(entity), &transform, entries);
+    }
+}
+
+impl SceneWorld {
+    /// See [plan_scene].
+    pub fn plan(&self) -> Vec<SpawnPlanEntry<'_>> {
+        plan_scene(self)
+    }
+}
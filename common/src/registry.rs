@@ -0,0 +1,62 @@
+use alloc::{boxed::Box, string::String};
+
+use crate::{EntityData, HashMap, ResourceData, WorldEntityJson, WorldResourceJson};
+
+type EntityParser = dyn Fn(&WorldEntityJson) -> EntityData + Send + Sync;
+type ResourceParser = dyn Fn(&WorldResourceJson) -> ResourceData + Send + Sync;
+
+/// Parsers for entity and resource types that the crate doesn't know, e.g. game specific
+/// nodes, which map them onto one of the built-in [EntityData] and [ResourceData] types.
+/// Registered parsers take precedence over the built-in ones,
+/// see [SceneWorldJson::to_world_with](crate::SceneWorldJson::to_world_with).
+#[derive(Default)]
+pub struct ParserRegistry {
+    entities: HashMap<String, Box<EntityParser>>,
+    resources: HashMap<String, Box<ResourceParser>>,
+}
+
+impl ParserRegistry {
+    /// Parses entities whose `type` is `entity_type` with `parser`, replacing the parser
+    /// registered before, if any.
+    pub fn register_entity_parser(
+        &mut self,
+        entity_type: impl Into<String>,
+        parser: impl Fn(&WorldEntityJson) -> EntityData + Send + Sync + 'static,
+    ) {
+        self.entities.insert(entity_type.into(), Box::new(parser));
+    }
+
+    /// Parses resources whose `type` is `resource_type` with `parser`, replacing the parser
+    /// registered before, if any.
+    pub fn register_resource_parser(
+        &mut self,
+        resource_type: impl Into<String>,
+        parser: impl Fn(&WorldResourceJson) -> ResourceData + Send + Sync + 'static,
+    ) {
+        self.resources
+            .insert(resource_type.into(), Box::new(parser));
+    }
+
+    /// Parses the entity if a parser was registered for its type.
+    pub fn parse_entity(&self, entity: &WorldEntityJson) -> Option<EntityData> {
+        self.entities
+            .get(&entity.entity_type)
+            .map(|parser| parser(entity))
+    }
+
+    /// Parses the resource if a parser was registered for its type.
+    pub fn parse_resource(&self, resource: &WorldResourceJson) -> Option<ResourceData> {
+        self.resources
+            .get(resource.resource_type())
+            .map(|parser| parser(resource))
+    }
+}
+
+impl core::fmt::Debug for ParserRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ParserRegistry")
+            .field("entities", &self.entities.keys())
+            .field("resources", &self.resources.keys())
+            .finish()
+    }
+}
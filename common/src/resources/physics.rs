@@ -1,8 +1,10 @@
+use alloc::vec::Vec;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct BoxCollisionShapeData {
-    pub size: Vec<f32>,
+    pub size: [f32; 3],
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -12,5 +14,44 @@ pub struct SphereCollisionShapeData {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ConcavePolygonCollisionShapeData {
+    /// Flat vertex positions, 3 values per vertex.
     pub data: Vec<f32>,
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct HeightMapCollisionShapeData {
+    pub width: usize,
+    pub depth: usize,
+
+    /// Row-major height samples, `width` samples per row.
+    pub data: Vec<f32>,
+}
+
+impl HeightMapCollisionShapeData {
+    /// Whether the samples fill the whole `width * depth` grid.
+    pub fn is_valid(&self) -> bool {
+        self.width >= 2 && self.depth >= 2 && self.width * self.depth == self.data.len()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WorldBoundaryCollisionShapeData {
+    /// Serde rejects normals that don't have exactly 3 elements.
+    pub normal: [f32; 3],
+    pub distance: f32,
+}
+
+impl WorldBoundaryCollisionShapeData {
+    /// Point on the plane closest to the local origin, i.e. `normal * distance`.
+    pub fn origin(&self) -> [f32; 3] {
+        let [x, y, z] = self.normal;
+        let length = libm::sqrtf(x * x + y * y + z * z);
+        let scale = if length > 0.0 {
+            self.distance / length
+        } else {
+            0.0
+        };
+
+        [x * scale, y * scale, z * scale]
+    }
+}
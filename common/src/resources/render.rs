@@ -1,15 +1,63 @@
+use alloc::{string::String, vec::Vec};
+
 use serde::{Deserialize, Serialize};
 
+/// Material of a primitive mesh, either the name of a material resource
+/// or a sub-resource material written inline by the exporter.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum MeshMaterial {
+    Resource(String),
+    Inline(StandardMaterialData),
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SphereMeshData {
     pub radius: f32,
-    pub material: Option<String>,
+
+    #[serde(rename = "radialSegments")]
+    pub radial_segments: Option<usize>,
+    pub rings: Option<usize>,
+    pub material: Option<MeshMaterial>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct BoxMeshData {
-    pub size: Vec<f32>,
-    pub material: Option<String>,
+    pub size: [f32; 3],
+
+    #[serde(rename = "subdivideWidth")]
+    pub subdivide_width: Option<usize>,
+
+    #[serde(rename = "subdivideHeight")]
+    pub subdivide_height: Option<usize>,
+
+    #[serde(rename = "subdivideDepth")]
+    pub subdivide_depth: Option<usize>,
+    pub material: Option<MeshMaterial>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PrismMeshData {
+    pub size: [f32; 3],
+
+    #[serde(rename = "leftToRight")]
+    pub left_to_right: Option<f32>,
+    pub material: Option<MeshMaterial>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TorusMeshData {
+    #[serde(rename = "innerRadius")]
+    pub inner_radius: f32,
+
+    #[serde(rename = "outerRadius")]
+    pub outer_radius: f32,
+
+    pub rings: Option<usize>,
+
+    #[serde(rename = "ringSegments")]
+    pub ring_segments: Option<usize>,
+    pub material: Option<MeshMaterial>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -24,14 +72,123 @@ pub struct Texture2DData {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct StandardMaterialData {
+    /// RGB or RGBA, see [StandardMaterialData::is_valid].
     #[serde(rename = "albedoColor")]
     pub albedo_color: Vec<f32>,
 
     #[serde(rename = "albedoTexture")]
     pub albedo_texture: Option<String>,
+
+    #[serde(rename = "normalTexture")]
+    pub normal_texture: Option<String>,
+
+    #[serde(rename = "metallicTexture")]
+    pub metallic_texture: Option<String>,
+
+    #[serde(rename = "roughnessTexture")]
+    pub roughness_texture: Option<String>,
+
+    pub metallic: Option<f32>,
+    pub roughness: Option<f32>,
+    pub emission: Option<Vec<f32>>,
+
+    #[serde(rename = "emissionEnergy")]
+    pub emission_energy: Option<f32>,
+
+    /// Absent means opaque, like Godot's default.
+    pub transparency: Option<Transparency>,
+
+    /// Alpha below which pixels are discarded with [Transparency::AlphaScissor].
+    #[serde(rename = "alphaScissorThreshold")]
+    pub alpha_scissor_threshold: Option<f32>,
+
+    /// Absent means back faces are culled, like Godot's default.
+    #[serde(rename = "cullMode")]
+    pub cull_mode: Option<CullMode>,
+
+    /// Color space of `albedoColor` and `emission`, absent means the load options decide.
+    #[serde(rename = "colorSpace")]
+    pub color_space: Option<ColorSpace>,
+}
+
+impl StandardMaterialData {
+    /// Whether the colors have 3 or 4 components, so that they can be read as RGB(A).
+    pub fn is_valid(&self) -> bool {
+        is_color(&self.albedo_color) && self.emission.as_deref().is_none_or(is_color)
+    }
+}
+
+fn is_color(color: &[f32]) -> bool {
+    matches!(color.len(), 3 | 4)
+}
+
+/// Color space of the material colors in a scene. Godot stores colors in sRGB,
+/// but some export paths write linear values instead.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+}
+
+/// Godot's `BaseMaterial3D.cull_mode`, which faces are not rendered.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CullMode {
+    #[default]
+    Back,
+    Front,
+
+    /// Double-sided, both faces are rendered.
+    Disabled,
+}
+
+/// Godot's `BaseMaterial3D.transparency` modes.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Transparency {
+    #[default]
+    Disabled,
+    Alpha,
+    AlphaScissor,
+    AlphaHash,
+    AlphaDepthPrePass,
+}
+
+/// Godot's `ORMMaterial3D`, a standard material whose occlusion, roughness and metallic values
+/// are packed into the red, green and blue channels of a single texture.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ORMMaterialData {
+    #[serde(flatten)]
+    pub material: StandardMaterialData,
+
+    #[serde(rename = "ormTexture")]
+    pub orm_texture: Option<String>,
+}
+
+/// Shaders aren't translated, only a fallback color is kept so that the mesh still renders.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ShaderMaterialData {
+    #[serde(rename = "albedoColor")]
+    pub albedo_color: Option<Vec<f32>>,
 }
 
+impl ShaderMaterialData {
+    /// Whether the fallback color, if any, has 3 or 4 components.
+    pub fn is_valid(&self) -> bool {
+        self.albedo_color.as_deref().is_none_or(is_color)
+    }
+}
+
+/// External scene resource. The exporter writes just the path, or an object with the path
+/// and the scene to instance when the file holds several.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct PackedSceneData {
     pub path: String,
+
+    /// Index of the scene in a glTF file, absent means the scene chosen by the load options.
+    /// glTF scenes are only addressed by index, so exporters map scene names to their index.
+    #[serde(rename = "sceneIndex", skip_serializing_if = "Option::is_none")]
+    pub scene_index: Option<usize>,
 }
@@ -0,0 +1,162 @@
+use std::{collections::HashMap, fmt, fs::File, io::BufReader, path::Path};
+
+use serde::{
+    de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserializer,
+};
+
+use crate::{
+    error::LoaderError, SceneFileError, WorldEntity, WorldEntityJson, WorldResource,
+    WorldResourceJson,
+};
+
+/// What is left of a streamed scene once all entities were handed out.
+#[derive(Debug)]
+pub struct StreamedScene {
+    pub resources: HashMap<String, WorldResource>,
+
+    /// Entities and resources left out because their data is malformed,
+    /// like [SceneWorld::errors](crate::SceneWorld::errors).
+    pub errors: Vec<LoaderError>,
+}
+
+/// Streams the entities of a scene file one top-level entity (with its children) at a time,
+/// so that memory use stays flat on large scenes instead of holding the whole entity tree.
+/// The resources are loaded first and passed along with every entity, then returned at the end.
+///
+/// Since exported scenes list the entities before the resources, the file is read twice.
+pub fn stream_scene_world_file<F>(
+    file: impl AsRef<Path>,
+    on_entity: F,
+) -> Result<StreamedScene, SceneFileError>
+where
+    F: FnMut(WorldEntity, &HashMap<String, WorldResource>),
+{
+    let open = || -> Result<_, SceneFileError> {
+        let file = File::open(file.as_ref()).map_err(SceneFileError::Io)?;
+        Ok(serde_json::Deserializer::from_reader(BufReader::new(file)))
+    };
+
+    let mut deserializer = open()?;
+    let (resources, mut errors) = deserializer
+        .deserialize_map(ResourcesVisitor)
+        .map_err(SceneFileError::Json)?;
+    deserializer.end().map_err(SceneFileError::Json)?;
+
+    let mut deserializer = open()?;
+    deserializer
+        .deserialize_map(EntitiesVisitor {
+            resources: &resources,
+            errors: &mut errors,
+            on_entity,
+        })
+        .map_err(SceneFileError::Json)?;
+    deserializer.end().map_err(SceneFileError::Json)?;
+
+    Ok(StreamedScene { resources, errors })
+}
+
+/// Reads only the resources of a scene, skipping over everything else.
+struct ResourcesVisitor;
+
+impl<'de> Visitor<'de> for ResourcesVisitor {
+    type Value = (HashMap<String, WorldResource>, Vec<LoaderError>);
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a scene world object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut resources = HashMap::new();
+        let mut errors = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "resources" {
+                let json: HashMap<String, WorldResourceJson> = map.next_value()?;
+                for (name, resource) in json {
+                    match resource.parse() {
+                        Ok(resource) => {
+                            resources.insert(name, resource);
+                        }
+                        Err(err) => errors.push(err.for_resource(&name)),
+                    }
+                }
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        Ok((resources, errors))
+    }
+}
+
+/// Hands the top-level entities of a scene to `on_entity`, skipping over everything else.
+struct EntitiesVisitor<'a, F> {
+    resources: &'a HashMap<String, WorldResource>,
+    errors: &'a mut Vec<LoaderError>,
+    on_entity: F,
+}
+
+impl<'de, 'a, F> Visitor<'de> for EntitiesVisitor<'a, F>
+where
+    F: FnMut(WorldEntity, &HashMap<String, WorldResource>),
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a scene world object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Self::Value, A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "entities" {
+                map.next_value_seed(EntitySeq {
+                    resources: self.resources,
+                    errors: &mut *self.errors,
+                    on_entity: &mut self.on_entity,
+                })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct EntitySeq<'a, F> {
+    resources: &'a HashMap<String, WorldResource>,
+    errors: &'a mut Vec<LoaderError>,
+    on_entity: &'a mut F,
+}
+
+impl<'de, 'a, F> DeserializeSeed<'de> for EntitySeq<'a, F>
+where
+    F: FnMut(WorldEntity, &HashMap<String, WorldResource>),
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'a, F> Visitor<'de> for EntitySeq<'a, F>
+where
+    F: FnMut(WorldEntity, &HashMap<String, WorldResource>),
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of entities")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while let Some(entity) = seq.next_element::<WorldEntityJson>()? {
+            if let Some(entity) = entity.parse(self.errors) {
+                (self.on_entity)(entity, self.resources);
+            }
+        }
+
+        Ok(())
+    }
+}
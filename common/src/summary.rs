@@ -0,0 +1,60 @@
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+
+use crate::{validate::ValidationIssue, HashMap, SceneWorld};
+
+/// Statistics of a parsed scene, e.g. for asserting on a scene in a build pipeline.
+#[derive(Debug, Clone, Default)]
+pub struct SceneSummary {
+    /// Number of entities, including children, by their Godot type.
+    pub entities_by_type: HashMap<String, usize>,
+
+    /// Number of resources by their Godot type, see [crate::ResourceData::type_name].
+    pub resources_by_type: HashMap<String, usize>,
+
+    /// References to resources that don't exist in the scene.
+    pub unresolved_references: Vec<ValidationIssue>,
+}
+
+impl SceneSummary {
+    pub fn entity_count(&self) -> usize {
+        self.entities_by_type.values().sum()
+    }
+
+    pub fn resource_count(&self) -> usize {
+        self.resources_by_type.values().sum()
+    }
+}
+
+impl SceneWorld {
+    pub fn summary(&self) -> SceneSummary {
+        let mut summary = SceneSummary::default();
+
+        for (entity, _, _) in self.iter_entities() {
+            *summary
+                .entities_by_type
+                .entry(entity.entity_type.clone())
+                .or_default() += 1;
+        }
+
+        for resource in self.resources.values() {
+            *summary
+                .resources_by_type
+                .entry(resource.data.type_name().to_owned())
+                .or_default() += 1;
+        }
+
+        summary.unresolved_references = self
+            .validate()
+            .into_iter()
+            .filter(|issue| {
+                matches!(
+                    issue,
+                    ValidationIssue::MissingResource { .. }
+                        | ValidationIssue::MissingMaterial { .. }
+                )
+            })
+            .collect();
+
+        summary
+    }
+}
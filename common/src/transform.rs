@@ -0,0 +1,112 @@
+use alloc::{borrow::ToOwned, string::String};
+
+/// Up-axis convention of an exported scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoordinateSystem {
+    /// Godot's own convention, no conversion is applied.
+    #[default]
+    YUp,
+
+    /// Right-handed Z-up, converted by rotating -90 degrees around the X axis.
+    ZUp,
+}
+
+impl CoordinateSystem {
+    /// Column-major 4x4 basis-change matrix converting from this coordinate system to Y-up.
+    pub fn to_y_up_matrix(&self) -> [f32; 16] {
+        match self {
+            CoordinateSystem::YUp => [
+                1.0, 0.0, 0.0, 0.0, //
+                0.0, 1.0, 0.0, 0.0, //
+                0.0, 0.0, 1.0, 0.0, //
+                0.0, 0.0, 0.0, 1.0,
+            ],
+            // (x, y, z) -> (x, z, -y), which keeps the handedness intact
+            CoordinateSystem::ZUp => [
+                1.0, 0.0, 0.0, 0.0, //
+                0.0, 0.0, -1.0, 0.0, //
+                0.0, 1.0, 0.0, 0.0, //
+                0.0, 0.0, 0.0, 1.0,
+            ],
+        }
+    }
+}
+
+/// A serialized transform didn't have a supported number of elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformError {
+    /// Name of the entity the transform belongs to, when known.
+    pub entity: Option<String>,
+    pub len: usize,
+}
+
+impl TransformError {
+    pub fn for_entity(self, entity: &str) -> Self {
+        Self {
+            entity: Some(entity.to_owned()),
+            ..self
+        }
+    }
+}
+
+impl core::fmt::Display for TransformError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.entity {
+            Some(entity) => write!(
+                f,
+                "transform of entity {} should have 12 or 16 elements, got {}",
+                entity, self.len
+            ),
+            None => write!(
+                f,
+                "transform should have 12 or 16 elements, got {}",
+                self.len
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TransformError {}
+
+/// Serialized identity transform, in Godot's `Transform3D` layout.
+pub const IDENTITY_TRANSFORM: [f32; 12] = [
+    1.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, //
+    0.0, 0.0, 1.0, //
+    0.0, 0.0, 0.0,
+];
+
+/// Expands a serialized Godot transform into a column-major 4x4 matrix.
+/// Both the full 16 floats and Godot's `Transform3D` layout of 12 floats
+/// (the three basis columns followed by the origin) are accepted.
+/// The matrix is either `f32` like the scene file, or widened to `f64` for large worlds.
+pub fn to_matrix_columns<T: From<f32> + Copy>(
+    transform: &[f32],
+) -> Result<[T; 16], TransformError> {
+    let x = |i: usize| T::from(transform[i]);
+    let (zero, one) = (T::from(0.0), T::from(1.0));
+
+    match transform.len() {
+        16 => Ok(core::array::from_fn(x)),
+        // Appends the homogeneous row below the basis columns and the origin
+        12 => Ok(core::array::from_fn(|i| match (i / 4, i % 4) {
+            (3, 3) => one,
+            (_, 3) => zero,
+            (col, row) => x(col * 3 + row),
+        })),
+        len => Err(TransformError { entity: None, len }),
+    }
+}
+
+/// Multiplies two column-major 4x4 matrices, e.g. a parent transform with a relative one.
+pub fn mul_matrix_columns(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
+    let mut result = [0.0; 16];
+    for col in 0..4 {
+        for row in 0..4 {
+            result[col * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[col * 4 + k]).sum();
+        }
+    }
+
+    result
+}
@@ -0,0 +1,294 @@
+use alloc::{borrow::ToOwned, string::String, vec, vec::Vec};
+
+use crate::{
+    error::LoaderError,
+    registry::ParserRegistry,
+    resources::render::MeshMaterial,
+    transform::{to_matrix_columns, TransformError},
+    EntityData, HashMap, ResourceData, SceneWorld, SceneWorldJson, WorldEntity, WorldEntityJson,
+    WorldResource,
+};
+
+/// A problem found in a scene before loading it, see [SceneWorld::validate].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// An entity references a resource that isn't in the resources map.
+    MissingResource {
+        entity: String,
+        resource: String,
+    },
+
+    /// An entity references a resource of the wrong kind, e.g. a mesh used as a collision shape.
+    WrongResourceType {
+        entity: String,
+        resource: String,
+        expected: &'static str,
+    },
+
+    /// A mesh references a material that isn't in the resources map.
+    MissingMaterial {
+        mesh: String,
+        material: String,
+    },
+
+    InvalidTransform(TransformError),
+
+    UnknownEntityType {
+        entity: String,
+        entity_type: String,
+    },
+    UnknownResourceType {
+        resource: String,
+        resource_type: String,
+    },
+
+    /// An entity or resource of a known type has malformed data, see [LoaderError].
+    InvalidData(LoaderError),
+}
+
+impl core::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationIssue::MissingResource { entity, resource } => write!(
+                f,
+                "entity {} references missing resource {}",
+                entity, resource
+            ),
+            ValidationIssue::WrongResourceType {
+                entity,
+                resource,
+                expected,
+            } => write!(
+                f,
+                "entity {} references resource {} which is not a {}",
+                entity, resource, expected
+            ),
+            ValidationIssue::MissingMaterial { mesh, material } => {
+                write!(f, "mesh {} references missing material {}", mesh, material)
+            }
+            ValidationIssue::InvalidTransform(err) => write!(f, "{}", err),
+            ValidationIssue::UnknownEntityType {
+                entity,
+                entity_type,
+            } => write!(f, "entity {} has unknown type {}", entity, entity_type),
+            ValidationIssue::UnknownResourceType {
+                resource,
+                resource_type,
+            } => write!(
+                f,
+                "resource {} has unknown type {}",
+                resource, resource_type
+            ),
+            ValidationIssue::InvalidData(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl SceneWorld {
+    /// Checks that all resource references resolve and that all transforms are well-formed,
+    /// reporting every problem instead of panicking at spawn time.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = vec![];
+
+        for (entity, _, _) in self.iter_entities() {
+            if let Err(err) = to_matrix_columns::<f32>(entity.data.transform()) {
+                issues.push(ValidationIssue::InvalidTransform(
+                    err.for_entity(&entity.name),
+                ));
+            }
+
+            match &entity.data {
+                EntityData::CollisionShape3D(shape) => {
+                    self.check_reference(entity, &shape.shape, "collision shape", &mut issues)
+                }
+                EntityData::MeshInstance3D(instance) => {
+                    self.check_reference(entity, &instance.mesh, "mesh", &mut issues)
+                }
+                EntityData::GridMap(grid) => {
+                    for name in grid.mesh_library.values() {
+                        let shape = self
+                            .resources
+                            .get(name)
+                            .map(|x| x.data.is_collision_shape());
+                        let expected = match shape {
+                            Some(true) => "collision shape",
+                            _ => "mesh",
+                        };
+                        self.check_reference(entity, name, expected, &mut issues);
+                    }
+                }
+                EntityData::ModelScene(scene) if scene.type_name == "MODEL" => {
+                    if let Some(path) = scene.data.as_str() {
+                        self.check_reference(entity, path, "packed scene", &mut issues)
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        issues
+    }
+
+    fn check_reference(
+        &self,
+        entity: &WorldEntity,
+        name: &str,
+        expected: &'static str,
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        let resource = match self.resources.get(name) {
+            Some(resource) => resource,
+            None => {
+                issues.push(ValidationIssue::MissingResource {
+                    entity: entity.name.clone(),
+                    resource: name.to_owned(),
+                });
+                return;
+            }
+        };
+
+        if resource_kind(resource) != Some(expected) {
+            issues.push(ValidationIssue::WrongResourceType {
+                entity: entity.name.clone(),
+                resource: name.to_owned(),
+                expected,
+            });
+            return;
+        }
+
+        if let Some(material) = mesh_material(resource) {
+            match self.resources.get(material) {
+                None => issues.push(ValidationIssue::MissingMaterial {
+                    mesh: name.to_owned(),
+                    material: material.clone(),
+                }),
+                Some(resource) if !resource.data.is_material() => {
+                    issues.push(ValidationIssue::WrongResourceType {
+                        entity: entity.name.clone(),
+                        resource: material.clone(),
+                        expected: "material",
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+impl SceneWorldJson {
+    /// Same as [SceneWorld::validate], but also reports unknown entity and resource types,
+    /// which [SceneWorldJson::to_world] only keeps as raw data, and malformed data, which it
+    /// leaves out. Entities of an unknown type or with malformed data are left out of the
+    /// remaining checks, along with their children.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        self.validate_with(&ParserRegistry::default())
+    }
+
+    /// Same as [SceneWorldJson::validate], but entity and resource types registered in `parsers`
+    /// are known.
+    pub fn validate_with(&self, parsers: &ParserRegistry) -> Vec<ValidationIssue> {
+        let mut issues = vec![];
+
+        let mut resources = HashMap::new();
+        for (name, resource) in &self.resources {
+            match resource.try_parse_data_with(parsers) {
+                Ok(Some(data)) => {
+                    resources.insert(name.clone(), WorldResource { data });
+                }
+                Err(err) => issues.push(ValidationIssue::InvalidData(err.for_resource(name))),
+                Ok(None) => issues.push(ValidationIssue::UnknownResourceType {
+                    resource: name.clone(),
+                    resource_type: resource.resource_type.clone(),
+                }),
+            }
+        }
+
+        let entities = self
+            .entities
+            .iter()
+            .filter_map(|entity| try_parse_entity(entity, parsers, &mut issues))
+            .collect();
+
+        let world = SceneWorld {
+            entities,
+            resources,
+            errors: vec![],
+        };
+        issues.extend(world.validate());
+
+        issues
+    }
+}
+
+fn try_parse_entity(
+    entity: &WorldEntityJson,
+    parsers: &ParserRegistry,
+    issues: &mut Vec<ValidationIssue>,
+) -> Option<WorldEntity> {
+    let data = entity.try_parse_data_with(parsers);
+    let children: Vec<WorldEntity> = entity
+        .children
+        .iter()
+        .flatten()
+        .filter_map(|child| try_parse_entity(child, parsers, issues))
+        .collect();
+
+    let data = match data {
+        Ok(Some(data)) => data,
+        Err(err) => {
+            issues.push(ValidationIssue::InvalidData(err));
+            return None;
+        }
+        Ok(None) => {
+            issues.push(ValidationIssue::UnknownEntityType {
+                entity: entity.name.clone(),
+                entity_type: entity.entity_type.clone(),
+            });
+            return None;
+        }
+    };
+
+    Some(WorldEntity {
+        name: entity.name.clone(),
+        entity_type: entity.entity_type.clone(),
+        data,
+        metadata: entity.metadata.clone(),
+        children: entity.children.as_ref().map(|_| children),
+    })
+}
+
+/// The kind of resource as referred to by entities.
+fn resource_kind(resource: &WorldResource) -> Option<&'static str> {
+    match &resource.data {
+        ResourceData::BoxMesh(_)
+        | ResourceData::SphereMesh(_)
+        | ResourceData::PrismMesh(_)
+        | ResourceData::TorusMesh(_)
+        | ResourceData::ArrayMesh(_) => Some("mesh"),
+
+        ResourceData::BoxCollisionShape(_)
+        | ResourceData::SphereCollisionShape(_)
+        | ResourceData::ConcavePolygonCollisionShape(_)
+        | ResourceData::HeightMapCollisionShape(_)
+        | ResourceData::WorldBoundaryCollisionShape(_) => Some("collision shape"),
+
+        ResourceData::PackedScene(_) => Some("packed scene"),
+        _ => None,
+    }
+}
+
+/// Name of the material resource referenced by a mesh, inline materials have none.
+fn mesh_material(resource: &WorldResource) -> Option<&String> {
+    let material = match &resource.data {
+        ResourceData::BoxMesh(mesh) => mesh.material.as_ref(),
+        ResourceData::SphereMesh(mesh) => mesh.material.as_ref(),
+        ResourceData::PrismMesh(mesh) => mesh.material.as_ref(),
+        ResourceData::TorusMesh(mesh) => mesh.material.as_ref(),
+        _ => None,
+    };
+
+    match material? {
+        MeshMaterial::Resource(name) => Some(name),
+        MeshMaterial::Inline(_) => None,
+    }
+}
@@ -0,0 +1,165 @@
+#![cfg(feature = "std")]
+
+use common::{
+    builder::{translation, SceneWorldBuilder},
+    entities::render::{GridCell, GridMapData},
+    error::LoaderError,
+    metadata::MetadataValue,
+    plan::SpawnPlan,
+    resources::{
+        physics::{HeightMapCollisionShapeData, WorldBoundaryCollisionShapeData},
+        render::{MeshMaterial, Texture2DData},
+    },
+    validate::ValidationIssue,
+    EntityData, HashMap, ResourceData, SceneWorld,
+};
+
+fn build_world() -> SceneWorld {
+    SceneWorldBuilder::new()
+        .add_static_body("Ground", translation([0.0, -1.0, 0.0]))
+        .with_box_collider([20.0, 1.0, 20.0])
+        .with_box_mesh([20.0, 1.0, 20.0])
+        .add_rigid_body("Ball", translation([0.0, 5.0, 0.0]))
+        .with_sphere_collider(0.5)
+        .with_sphere_mesh(0.5)
+        .with_metadata("bouncy", MetadataValue::Bool(true))
+        .add_area("Trigger", translation([3.0, 0.0, 0.0]))
+        .with_box_collider([2.0, 2.0, 2.0])
+        .add_node("Spawn", translation([0.0, 1.0, 0.0]))
+        .build()
+}
+
+#[test]
+fn builds_entity_tree() {
+    let world = build_world();
+
+    let roots: Vec<_> = world.entities.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(roots, ["Ground", "Ball", "Trigger", "Spawn"]);
+    assert_eq!(world.iter_entities().count(), 9);
+    assert_eq!(world.resources.len(), 5);
+
+    let ball = &world.entities[1];
+    assert_eq!(ball.entity_type, "RigidBody3D");
+    assert_eq!(ball.metadata["bouncy"].as_bool(), Some(true));
+    assert_eq!(ball.data.transform()[9..], [0.0, 5.0, 0.0]);
+}
+
+#[test]
+fn references_allocated_resources() {
+    let world = build_world();
+
+    // Every entity resolves, so nothing is left to validate
+    assert!(world.validate().is_empty());
+    for entry in world.plan() {
+        assert!(entry.plan.is_ok(), "{} failed to plan", entry.entity.name);
+    }
+
+    let sensors = world
+        .plan()
+        .into_iter()
+        .filter(|x| matches!(x.plan, Ok(SpawnPlan::Collider { sensor: true, .. })))
+        .count();
+    assert_eq!(sensors, 1);
+}
+
+#[test]
+fn matches_parsed_scene() {
+    let world = build_world();
+    let parsed = world.to_json().to_world();
+
+    assert_eq!(parsed.resources.len(), world.resources.len());
+    for (entity, _, _) in world.iter_entities() {
+        let again = parsed
+            .iter_entities()
+            .find(|(x, _, _)| x.name == entity.name)
+            .unwrap()
+            .0;
+
+        assert_eq!(again.entity_type, entity.entity_type);
+        assert_eq!(again.data.to_value(), entity.data.to_value());
+        assert!(!matches!(again.data, EntityData::Unknown { .. }));
+    }
+    for (name, resource) in &world.resources {
+        assert_eq!(
+            parsed.resources[name].data.to_value(),
+            resource.data.to_value()
+        );
+    }
+}
+
+#[test]
+fn rejects_invalid_grid_map_shapes() {
+    let mut builder = SceneWorldBuilder::new();
+    let height_map = builder.add_resource(ResourceData::HeightMapCollisionShape(
+        HeightMapCollisionShapeData {
+            width: 3,
+            depth: 3,
+            data: vec![0.0; 4],
+        },
+    ));
+    let boundary = builder.add_resource(ResourceData::WorldBoundaryCollisionShape(
+        WorldBoundaryCollisionShapeData {
+            normal: [0.0; 3],
+            distance: 0.0,
+        },
+    ));
+
+    let grid_map = |shape: &str| {
+        EntityData::GridMap(GridMapData {
+            transform: translation([0.0; 3]).to_vec(),
+            cell_size: None,
+            cells: vec![GridCell {
+                position: [0, 0, 0],
+                item: 0,
+            }],
+            mesh_library: HashMap::from([(0, shape.to_owned())]),
+        })
+    };
+    let world = builder
+        .add_entity("Terrain", grid_map(&height_map))
+        .add_entity("Floor", grid_map(&boundary))
+        .build();
+
+    let invalid: Vec<_> = world
+        .plan()
+        .into_iter()
+        .filter_map(|entry| match entry.plan {
+            Err(LoaderError::InvalidResource { resource, .. }) => Some(resource),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(invalid, [height_map, boundary]);
+}
+
+#[test]
+fn rejects_materials_that_are_not_materials() {
+    let mut builder = SceneWorldBuilder::new();
+    let texture = builder.add_resource(ResourceData::Texture2D(Texture2DData {
+        path: "res://crate.png".to_owned(),
+    }));
+    let mut world = builder
+        .add_static_body("Crate", translation([0.0; 3]))
+        .with_box_mesh([1.0, 1.0, 1.0])
+        .build();
+    for resource in world.resources.values_mut() {
+        if let ResourceData::BoxMesh(mesh) = &mut resource.data {
+            mesh.material = Some(MeshMaterial::Resource(texture.clone()));
+        }
+    }
+
+    assert!(matches!(
+        world.validate().as_slice(),
+        [ValidationIssue::WrongResourceType { resource, expected: "material", .. }]
+            if *resource == texture
+    ));
+
+    let mesh = world
+        .plan()
+        .into_iter()
+        .find(|entry| matches!(entry.entity.data, EntityData::MeshInstance3D(_)))
+        .unwrap();
+    assert!(matches!(
+        mesh.plan,
+        Err(LoaderError::InvalidResource { resource, .. }) if resource == texture
+    ));
+}
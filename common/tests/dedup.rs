@@ -0,0 +1,114 @@
+#![cfg(feature = "std")]
+
+use common::{
+    builder::{translation, SceneWorldBuilder},
+    resources::render::{MeshMaterial, StandardMaterialData, Texture2DData},
+    EntityData, ResourceData, SceneWorld,
+};
+use serde_json::json;
+
+fn crates() -> SceneWorldBuilder {
+    SceneWorldBuilder::new()
+        .add_rigid_body("CrateA", translation([0.0, 1.0, 0.0]))
+        .with_box_collider([1.0, 1.0, 1.0])
+        .with_box_mesh([1.0, 1.0, 1.0])
+        .add_rigid_body("CrateB", translation([2.0, 1.0, 0.0]))
+        .with_box_collider([1.0, 1.0, 1.0])
+        .with_box_mesh([1.0, 1.0, 1.0])
+        .add_rigid_body("Ball", translation([4.0, 1.0, 0.0]))
+        .with_sphere_collider(0.5)
+}
+
+/// Names of the resources referenced by the entities of the world.
+fn references(world: &SceneWorld) -> Vec<String> {
+    world
+        .iter_entities()
+        .filter_map(|(entity, _, _)| match &entity.data {
+            EntityData::CollisionShape3D(shape) => Some(shape.shape.clone()),
+            EntityData::MeshInstance3D(instance) => Some(instance.mesh.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn merges_identical_resources() {
+    let mut world = crates().build();
+    assert_eq!(world.resources.len(), 5);
+
+    let remap = world.deduplicate_resources();
+    assert_eq!(world.resources.len(), 3);
+    assert_eq!(remap.len(), 2);
+    assert_eq!(remap["BoxShape3D_5"], "BoxShape3D_1");
+    assert_eq!(remap["BoxMesh_7"], "BoxMesh_3");
+
+    for name in references(&world) {
+        assert!(world.resources.contains_key(&name), "{} was dropped", name);
+    }
+    assert!(world.deduplicate_resources().is_empty());
+}
+
+#[test]
+fn merges_resources_that_only_differ_in_merged_references() {
+    let mut builder = crates();
+    let mut meshes = vec![];
+    for _ in 0..2 {
+        let texture = builder.add_resource(ResourceData::Texture2D(Texture2DData {
+            path: "res://crate.png".to_owned(),
+        }));
+        let material: StandardMaterialData =
+            serde_json::from_value(json!({"albedoColor": [1, 1, 1, 1], "albedoTexture": texture}))
+                .unwrap();
+        let material = builder.add_resource(ResourceData::StandardMaterial(material));
+        meshes.push(material);
+    }
+    let mut world = builder.build();
+    for (mesh, material) in ["BoxMesh_3", "BoxMesh_7"].into_iter().zip(meshes) {
+        let ResourceData::BoxMesh(mesh) = &mut world.resources.get_mut(mesh).unwrap().data else {
+            panic!("builder should name box meshes after their type");
+        };
+        mesh.material = Some(MeshMaterial::Resource(material));
+    }
+
+    // Textures first, which makes the materials identical, and then the meshes
+    let remap = world.deduplicate_resources();
+    assert_eq!(remap.len(), 4);
+    assert_eq!(world.textures().count(), 1);
+    assert_eq!(world.materials().count(), 1);
+    assert_eq!(world.meshes().count(), 1);
+
+    let (texture, _) = world.textures().next().unwrap();
+    let (_, material) = world.materials().next().unwrap();
+    let ResourceData::StandardMaterial(material) = material else {
+        panic!("material should be a standard material");
+    };
+    assert_eq!(material.albedo_texture.as_deref(), Some(texture));
+
+    // Every dropped name maps to a resource that is still there
+    for canonical in remap.values() {
+        assert!(world.resources.contains_key(canonical));
+    }
+}
+
+#[test]
+fn keeps_unknown_resources() {
+    let mut builder = crates();
+    let mut curves = vec![];
+    for _ in 0..2 {
+        curves.push(builder.add_resource(ResourceData::Unknown {
+            resource_type: "Curve3D".to_owned(),
+            data: json!({"points": [0, 0, 0, 1, 0, 0]}),
+        }));
+    }
+    let path = EntityData::Unknown {
+        entity_type: "Path3D".to_owned(),
+        data: json!({"curve": curves[1]}),
+    };
+    let mut world = builder.add_entity("Path", path).build();
+
+    let remap = world.deduplicate_resources();
+    assert_eq!(remap.len(), 2);
+    for curve in &curves {
+        assert!(world.resources.contains_key(curve), "{} was merged", curve);
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "std")]
+
+use common::{
+    entities::physics::{BodyActivation, ColliderMaterial, MassOverride, RigidBodyData},
+    metadata::Metadata,
+};
+
+fn metadata(json: &str) -> Metadata {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn reads_collider_material() {
+    let material = ColliderMaterial::from_metadata(&metadata(r#"{"friction": 0.2, "density": 0}"#));
+    assert_eq!(
+        material,
+        ColliderMaterial {
+            friction: Some(0.2),
+            restitution: None,
+            density: Some(0.0),
+        }
+    );
+}
+
+#[test]
+fn reads_mass_override() {
+    let mass = MassOverride::from_metadata(&metadata(
+        r#"{"center_of_mass": [0, -0.5, 0.25], "mass": 1200}"#,
+    ));
+    assert_eq!(
+        mass,
+        Ok(Some(MassOverride {
+            mass: 1200.0,
+            center_of_mass: [0.0, -0.5, 0.25],
+            principal_inertia: [0.0; 3],
+        }))
+    );
+
+    // Without a center of mass the mass is left to the colliders
+    assert_eq!(
+        MassOverride::from_metadata(&metadata(r#"{"mass": 5}"#)),
+        Ok(None)
+    );
+}
+
+#[test]
+fn rejects_malformed_mass_override() {
+    for json in [
+        r#"{"center_of_mass": [0, 1]}"#,
+        r#"{"center_of_mass": "low"}"#,
+        r#"{"center_of_mass": [0, 0, 0], "inertia": [1, 1, 1, 1]}"#,
+        r#"{"center_of_mass": [0, 0, 0], "mass": "heavy"}"#,
+    ] {
+        assert!(
+            MassOverride::from_metadata(&metadata(json)).is_err(),
+            "{}",
+            json
+        );
+    }
+}
+
+fn rigid_body(json: &str) -> RigidBodyData {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn frozen_and_sleeping_bodies_start_asleep() {
+    let body = rigid_body(r#"{"transform": [], "freeze": true}"#);
+    assert_eq!(body.activation(), BodyActivation::Asleep);
+
+    let body = rigid_body(r#"{"transform": [], "sleeping": true, "canSleep": true}"#);
+    assert_eq!(body.activation(), BodyActivation::Asleep);
+
+    let body = rigid_body(r#"{"transform": [], "freeze": false}"#);
+    assert_eq!(body.activation(), BodyActivation::Awake);
+}
+
+#[test]
+fn disabled_process_mode_disables_the_body() {
+    let body = rigid_body(r#"{"transform": [], "sleeping": true, "processMode": "disabled"}"#);
+    assert_eq!(body.activation(), BodyActivation::Disabled);
+
+    let body = rigid_body(r#"{"transform": [], "processMode": "always"}"#);
+    assert_eq!(body.activation(), BodyActivation::Awake);
+}
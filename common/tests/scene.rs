@@ -0,0 +1,588 @@
+#![cfg(feature = "std")]
+
+use common::{
+    error::LoaderError,
+    plan::plan_entity,
+    resources::{physics::SphereCollisionShapeData, render::MeshMaterial},
+    transform::to_matrix_columns,
+    try_load_scene_world_file,
+    validate::ValidationIssue,
+    EntityData, ResourceData, SceneWorld, SceneWorldJson, WorldEntity,
+};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/scene.json");
+
+fn load_fixture() -> SceneWorld {
+    try_load_scene_world_file(FIXTURE).expect("fixture should load")
+}
+
+fn entity<'a>(world: &'a SceneWorld, name: &str) -> &'a WorldEntity {
+    world
+        .find_entity(name)
+        .unwrap_or_else(|| panic!("fixture should contain entity {}", name))
+}
+
+fn translation(entity: &WorldEntity) -> [f32; 3] {
+    let matrix = to_matrix_columns::<f32>(entity.data.transform()).unwrap();
+    [matrix[12], matrix[13], matrix[14]]
+}
+
+#[test]
+fn parses_entity_tree() {
+    let world = load_fixture();
+
+    let roots: Vec<&str> = world.entities.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(
+        roots,
+        [
+            "Ground",
+            "Ball",
+            "Platform",
+            "Trigger",
+            "Floor",
+            "Pin",
+            "Hinge",
+            "Sign",
+            "Rig",
+            "Tiles",
+            "Camera3D",
+            "Props",
+            "AnimationPlayer",
+            "Sun",
+        ]
+    );
+
+    let ground = entity(&world, "Ground");
+    let children: Vec<&str> = ground
+        .children
+        .iter()
+        .flatten()
+        .map(|x| x.name.as_str())
+        .collect();
+    assert_eq!(
+        children,
+        [
+            "Ground/MeshInstance3D",
+            "Ground/CollisionShape3D",
+            "Ground/HeightMap"
+        ]
+    );
+    assert!(entity(&world, "Platform").children.is_none());
+
+    let summary = world.summary();
+    assert_eq!(summary.entity_count(), 23);
+    assert_eq!(summary.entities_by_type["CollisionShape3D"], 5);
+    assert_eq!(summary.entities_by_type["MeshInstance3D"], 3);
+    assert_eq!(summary.resource_count(), 17);
+    assert!(summary.unresolved_references.is_empty());
+}
+
+/// Path of an entity in the fixture along with a check of its parsed type.
+type Expectation = (&'static str, fn(&EntityData) -> bool);
+
+#[test]
+fn parses_every_entity_type() {
+    let world = load_fixture();
+
+    let expectations: [Expectation; 16] = [
+        ("Ground", |x| matches!(x, EntityData::StaticBody3D(_))),
+        ("Ball", |x| matches!(x, EntityData::RigidBody3D(_))),
+        ("Platform", |x| matches!(x, EntityData::KinematicBody3D(_))),
+        ("Ground/CollisionShape3D", |x| {
+            matches!(x, EntityData::CollisionShape3D(_))
+        }),
+        ("Trigger", |x| matches!(x, EntityData::Area3D(_))),
+        ("Pin", |x| matches!(x, EntityData::PinJoint3D(_))),
+        ("Hinge", |x| matches!(x, EntityData::HingeJoint3D(_))),
+        ("Props/House", |x| matches!(x, EntityData::ModelScene(_))),
+        ("Ground/MeshInstance3D", |x| {
+            matches!(x, EntityData::MeshInstance3D(_))
+        }),
+        ("Sign", |x| matches!(x, EntityData::Label3D(_))),
+        ("Rig", |x| matches!(x, EntityData::Skeleton3D(_))),
+        ("Tiles", |x| matches!(x, EntityData::GridMap(_))),
+        ("Camera3D", |x| matches!(x, EntityData::Camera(_))),
+        ("Props", |x| matches!(x, EntityData::Node3D(_))),
+        ("AnimationPlayer", |x| {
+            matches!(x, EntityData::AnimationPlayer(_))
+        }),
+        ("Sun", |x| matches!(x, EntityData::Unknown { .. })),
+    ];
+
+    for (name, is_expected) in expectations {
+        let entity = entity(&world, name);
+        assert!(
+            is_expected(&entity.data),
+            "{} parsed as {:?}",
+            name,
+            entity.data
+        );
+    }
+
+    let EntityData::Unknown { entity_type, data } = &entity(&world, "Sun").data else {
+        unreachable!()
+    };
+    assert_eq!(entity_type, "DirectionalLight3D");
+    assert_eq!(data["energy"], 2);
+}
+
+#[test]
+fn parses_every_resource_type() {
+    let world = load_fixture();
+
+    let expectations = [
+        ("BoxMesh_ground", "BoxMesh"),
+        ("SphereMesh_ball", "SphereMesh"),
+        ("PrismMesh_ramp", "PrismMesh"),
+        ("TorusMesh_ring", "TorusMesh"),
+        ("ArrayMesh_tree", "ArrayMesh"),
+        ("StandardMaterial3D_grass", "StandardMaterial3D"),
+        ("ORMMaterial3D_stone", "ORMMaterial3D"),
+        ("ShaderMaterial_glow", "ShaderMaterial"),
+        ("Texture2D_grass", "Texture2D"),
+        ("BoxShape3D_ground", "BoxShape3D"),
+        ("SphereShape3D_ball", "SphereShape3D"),
+        ("ConcavePolygonShape3D_ramp", "ConcavePolygonShape3D"),
+        ("HeightMapShape3D_hills", "HeightMapShape3D"),
+        ("WorldBoundaryShape3D_floor", "WorldBoundaryShape3D"),
+        ("PackedScene_house", "PackedScene"),
+        ("Environment_sky", "Environment"),
+    ];
+
+    for (name, type_name) in expectations {
+        assert_eq!(
+            world.resources[name].data.type_name(),
+            type_name,
+            "{}",
+            name
+        );
+    }
+
+    assert!(matches!(
+        world.resources["Environment_sky"].data,
+        ResourceData::Unknown { .. }
+    ));
+    assert_eq!(
+        world.resources["ArrayMesh_tree"]
+            .as_array_mesh()
+            .unwrap()
+            .path,
+        "res://models/tree.glb"
+    );
+    assert_eq!(
+        world.resources["PackedScene_house"]
+            .as_packed_scene()
+            .unwrap()
+            .path,
+        "res://scenes/house.tscn"
+    );
+    assert!(world.resources["HeightMapShape3D_hills"]
+        .as_height_map_shape()
+        .unwrap()
+        .is_valid());
+    assert_eq!(
+        world.resources["WorldBoundaryShape3D_floor"]
+            .as_world_boundary_shape()
+            .unwrap()
+            .origin(),
+        [0.0, -10.0, 0.0]
+    );
+}
+
+#[test]
+fn expands_transforms() {
+    let world = load_fixture();
+
+    assert_eq!(translation(entity(&world, "Ground")), [0.0, -1.0, 0.0]);
+    assert_eq!(translation(entity(&world, "Props/Tree")), [1.0, 0.0, 1.0]);
+    // Godot's 12 float layout, with the origin last
+    assert_eq!(translation(entity(&world, "Ball")), [2.0, 5.0, -3.0]);
+    // Entities that aren't 3D nodes are placed at their parent
+    assert_eq!(translation(entity(&world, "AnimationPlayer")), [0.0; 3]);
+    assert_eq!(translation(entity(&world, "Sun")), [0.0; 3]);
+}
+
+#[test]
+fn resolves_referenced_resources() {
+    let world = load_fixture();
+
+    let EntityData::MeshInstance3D(instance) = &entity(&world, "Ground/MeshInstance3D").data else {
+        panic!("ground mesh should be a mesh instance");
+    };
+    let mesh = world.resources[&instance.mesh].as_box_mesh().unwrap();
+    assert_eq!(mesh.size, [20.0, 2.0, 20.0]);
+
+    let Some(MeshMaterial::Resource(material)) = &mesh.material else {
+        panic!("ground mesh should reference its material");
+    };
+    let material = world.resources[material].as_material().unwrap();
+    assert_eq!(material.albedo_texture.as_deref(), Some("Texture2D_grass"));
+    assert_eq!(
+        world.resources["Texture2D_grass"]
+            .as_texture()
+            .unwrap()
+            .path,
+        "res://textures/grass.png"
+    );
+
+    let EntityData::CollisionShape3D(shape) = &entity(&world, "Ball/CollisionShape3D").data else {
+        panic!("ball shape should be a collision shape");
+    };
+    assert_eq!(
+        world.resources[&shape.shape]
+            .as_sphere_shape()
+            .unwrap()
+            .radius,
+        0.5
+    );
+    assert!(!shape.is_disabled());
+
+    let EntityData::CollisionShape3D(hills) = &entity(&world, "Ground/HeightMap").data else {
+        panic!("height map should be a collision shape");
+    };
+    assert!(hills.is_disabled());
+
+    let EntityData::GridMap(tiles) = &entity(&world, "Tiles").data else {
+        panic!("tiles should be a grid map");
+    };
+    let items: Vec<&str> = tiles.items().map(|(_, resource)| resource).collect();
+    assert_eq!(items, ["PrismMesh_ramp", "TorusMesh_ring"]);
+    assert_eq!(
+        tiles.cell_transform(&tiles.cells[1])[12..15],
+        [1.5, 0.5, 2.5]
+    );
+}
+
+#[test]
+fn applies_renamed_fields() {
+    let world = load_fixture();
+
+    let material = world.resources["StandardMaterial3D_grass"]
+        .as_material()
+        .unwrap();
+    assert_eq!(material.albedo_color, [0.2, 0.8, 0.1, 1.0]);
+
+    let orm = world.resources["ORMMaterial3D_stone"]
+        .as_orm_material()
+        .unwrap();
+    assert_eq!(orm.material.albedo_color, [0.5, 0.5, 0.5, 1.0]);
+    assert_eq!(orm.orm_texture.as_deref(), Some("Texture2D_stone_orm"));
+
+    let sphere = world.resources["SphereMesh_ball"].as_sphere_mesh().unwrap();
+    let Some(MeshMaterial::Inline(inline)) = &sphere.material else {
+        panic!("ball mesh should have an inline material");
+    };
+    assert_eq!(inline.albedo_color, [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(sphere.radial_segments, Some(32));
+
+    let EntityData::RigidBody3D(ball) = &entity(&world, "Ball").data else {
+        panic!("ball should be a rigid body");
+    };
+    assert_eq!(ball.linear_velocity, Some([1.0, 0.0, -2.0]));
+    assert_eq!(ball.angular_velocity, Some([0.0, 3.0, 0.0]));
+    assert_eq!(ball.gravity_scale, Some(0.5));
+
+    let EntityData::KinematicBody3D(platform) = &entity(&world, "Platform").data else {
+        panic!("platform should be a kinematic body");
+    };
+    assert_eq!(platform.linear_velocity, Some([0.0, 0.0, 1.5]));
+
+    let EntityData::HingeJoint3D(hinge) = &entity(&world, "Hinge").data else {
+        panic!("hinge should be a hinge joint");
+    };
+    assert_eq!(hinge.node_a.as_deref(), Some("Platform"));
+    assert_eq!(hinge.angular_limit, Some([-1.5, 1.5]));
+
+    let EntityData::Label3D(sign) = &entity(&world, "Sign").data else {
+        panic!("sign should be a label");
+    };
+    assert_eq!(sign.font_size, 48.0);
+}
+
+#[test]
+fn keeps_metadata() {
+    let world = load_fixture();
+
+    let ball = entity(&world, "Ball");
+    assert_eq!(
+        ball.metadata.get("points").and_then(|x| x.as_i64()),
+        Some(10)
+    );
+    assert_eq!(
+        ball.metadata.get("bouncy").and_then(|x| x.as_bool()),
+        Some(true)
+    );
+    assert_eq!(
+        entity(&world, "Ground")
+            .metadata
+            .get("surface")
+            .and_then(|x| x.as_str()),
+        Some("grass")
+    );
+}
+
+#[test]
+fn validates_fixture() {
+    let json: SceneWorldJson =
+        serde_json::from_str(&std::fs::read_to_string(FIXTURE).unwrap()).unwrap();
+
+    // Only the types the crate doesn't know are reported
+    assert_eq!(json.validate().len(), 2);
+    assert!(json.to_world().validate().is_empty());
+}
+
+#[test]
+fn round_trips_through_json() {
+    let world = load_fixture();
+    let again = world.to_json().to_world();
+
+    assert_eq!(
+        again.summary().entity_count(),
+        world.summary().entity_count()
+    );
+    assert_eq!(again.resources.len(), world.resources.len());
+    for (entity, _, _) in world.iter_entities() {
+        assert_eq!(
+            entity.data.to_value(),
+            self::entity(&again, &entity.name).data.to_value()
+        );
+    }
+}
+
+#[test]
+fn computes_bounds() {
+    let world = load_fixture();
+    let bounds = world.bounds();
+
+    // The ground box spans the floor, the ball is the highest and the height map the farthest
+    let aabb = bounds.aabb.unwrap();
+    assert_eq!(aabb.min, [-10.0, -2.0, -10.0]);
+    assert_eq!(aabb.max, [10.0, 5.5, 10.5]);
+    assert_eq!(world.compute_bounds(), Some(aabb));
+
+    assert_eq!(
+        bounds.unknown,
+        ["Floor/CollisionShape3D", "Props/Tree", "Props/House"]
+    );
+}
+
+fn sorted_names<'a, T>(resources: impl Iterator<Item = (&'a str, T)>) -> Vec<&'a str> {
+    let mut names: Vec<&str> = resources.map(|(name, _)| name).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn queries_resources_by_type() {
+    let world = load_fixture();
+
+    let textures = sorted_names(world.textures());
+    assert_eq!(textures, ["Texture2D_grass", "Texture2D_stone_orm"]);
+
+    let spheres = sorted_names(world.resources_of_type::<SphereCollisionShapeData>());
+    assert_eq!(spheres, ["SphereShape3D_ball"]);
+
+    let materials = sorted_names(world.materials());
+    assert_eq!(
+        materials,
+        [
+            "ORMMaterial3D_stone",
+            "ShaderMaterial_glow",
+            "StandardMaterial3D_grass"
+        ]
+    );
+    assert_eq!(world.meshes().count(), 5);
+    assert_eq!(world.collision_shapes().count(), 5);
+}
+
+/// Scene with a malformed box shape, and a malformed body whose child is left out with it.
+fn malformed_json() -> SceneWorldJson {
+    serde_json::from_value(serde_json::json!({
+        "entities": [
+            {
+                "name": "Crate",
+                "type": "CollisionShape3D",
+                "data": {"shape": "BoxShape3D_bad", "transform": [1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0]},
+                "metadata": {},
+                "children": null
+            },
+            {
+                "name": "Body",
+                "type": "RigidBody3D",
+                "data": {"transform": "not a transform"},
+                "metadata": {},
+                "children": [
+                    {
+                        "name": "Body/Child",
+                        "type": "Node3D",
+                        "data": {"transform": [1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0]},
+                        "metadata": {},
+                        "children": null
+                    }
+                ]
+            }
+        ],
+        "resources": {
+            "BoxShape3D_bad": {"type": "BoxShape3D", "data": {"size": "big"}},
+            "Texture2D_bad": {"type": "Texture2D", "data": 42}
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn skips_malformed_entities_and_resources() {
+    let world = malformed_json().to_world();
+
+    assert!(world.resources.is_empty());
+    let names: Vec<&str> = world
+        .iter_entities()
+        .map(|(x, _, _)| x.name.as_str())
+        .collect();
+    assert_eq!(names, ["Crate"]);
+
+    let mut failed: Vec<&str> = world
+        .errors
+        .iter()
+        .map(|err| match err {
+            LoaderError::InvalidResource { resource, .. } => resource.as_str(),
+            LoaderError::InvalidEntity { entity, .. } => entity.as_str(),
+            err => panic!("unexpected error {}", err),
+        })
+        .collect();
+    failed.sort();
+    assert_eq!(failed, ["Body", "BoxShape3D_bad", "Texture2D_bad"]);
+}
+
+#[test]
+fn validation_reports_malformed_data() {
+    let issues = malformed_json().validate();
+
+    let invalid: Vec<&ValidationIssue> = issues
+        .iter()
+        .filter(|x| matches!(x, ValidationIssue::InvalidData(_)))
+        .collect();
+    assert_eq!(invalid.len(), 3);
+
+    // The shape entity is fine, but its resource was left out
+    assert!(issues.contains(&ValidationIssue::MissingResource {
+        entity: "Crate".to_owned(),
+        resource: "BoxShape3D_bad".to_owned(),
+    }));
+}
+
+#[test]
+fn rejects_world_boundary_normals_without_3_elements() {
+    let json: SceneWorldJson = serde_json::from_value(serde_json::json!({
+        "entities": [],
+        "resources": {
+            "WorldBoundaryShape3D_short": {
+                "type": "WorldBoundaryShape3D",
+                "data": {"normal": [0, 1], "distance": 1}
+            }
+        }
+    }))
+    .unwrap();
+    let world = json.to_world();
+
+    assert!(world.resources.is_empty());
+    assert!(matches!(
+        &world.errors[..],
+        [LoaderError::InvalidResource { resource, .. }] if resource == "WorldBoundaryShape3D_short"
+    ));
+}
+
+/// Scene whose vectors have the wrong number of elements, which used to panic when spawning.
+fn malformed_lengths_json() -> SceneWorldJson {
+    let identity = [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1];
+    let entity = |name: &str, entity_type: &str, data: serde_json::Value| {
+        let mut data = data;
+        data["transform"] = serde_json::json!(identity);
+        serde_json::json!({
+            "name": name,
+            "type": entity_type,
+            "data": data,
+            "metadata": {},
+            "children": null
+        })
+    };
+
+    serde_json::from_value(serde_json::json!({
+        "entities": [
+            entity("Crate", "CollisionShape3D", serde_json::json!({"shape": "BoxShape3D_short"})),
+            entity("Ramp", "CollisionShape3D", serde_json::json!({"shape": "ConcavePolygonShape3D_partial"})),
+            entity("Wall", "MeshInstance3D", serde_json::json!({"mesh": "BoxMesh_short", "visible": true})),
+            entity("Roof", "MeshInstance3D", serde_json::json!({"mesh": "PrismMesh_short", "visible": true})),
+            entity("Sign", "MeshInstance3D", serde_json::json!({"mesh": "BoxMesh_tinted", "visible": true})),
+            entity("Platform", "CharacterBody3D", serde_json::json!({"linearVelocity": [0, 1]})),
+            entity("Ball", "RigidBody3D", serde_json::json!({"angularVelocity": [1]})),
+        ],
+        "resources": {
+            "BoxShape3D_short": {"type": "BoxShape3D", "data": {"size": [1, 2]}},
+            "ConcavePolygonShape3D_partial": {
+                "type": "ConcavePolygonShape3D",
+                "data": {"data": [0, 0, 0, 1]}
+            },
+            "BoxMesh_short": {"type": "BoxMesh", "data": {"size": [1]}},
+            "PrismMesh_short": {"type": "PrismMesh", "data": {"size": [1, 1]}},
+            "BoxMesh_tinted": {
+                "type": "BoxMesh",
+                "data": {"size": [1, 1, 1], "material": "StandardMaterial3D_short"}
+            },
+            "StandardMaterial3D_short": {
+                "type": "StandardMaterial3D",
+                "data": {"albedoColor": [1, 0]}
+            }
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn skips_entities_with_malformed_lengths() {
+    let world = malformed_lengths_json().to_world();
+
+    // Vectors of the wrong length fail to parse, along with the bodies that have them
+    let mut failed: Vec<&str> = world
+        .errors
+        .iter()
+        .map(|err| match err {
+            LoaderError::InvalidResource { resource, .. } => resource.as_str(),
+            LoaderError::InvalidEntity { entity, .. } => entity.as_str(),
+            err => panic!("unexpected error {}", err),
+        })
+        .collect();
+    failed.sort();
+    assert_eq!(
+        failed,
+        [
+            "Ball",
+            "BoxMesh_short",
+            "BoxShape3D_short",
+            "Platform",
+            "PrismMesh_short"
+        ]
+    );
+
+    // Lengths that still parse are rejected when planning the entities that use them
+    let plan = |name: &str| plan_entity(entity(&world, name), false, &world.resources);
+    for (name, resource) in [
+        ("Crate", "BoxShape3D_short"),
+        ("Wall", "BoxMesh_short"),
+        ("Roof", "PrismMesh_short"),
+    ] {
+        assert!(matches!(
+            plan(name),
+            Err(LoaderError::MissingResource { resource: missing, .. }) if missing == resource
+        ));
+    }
+    for (name, resource) in [
+        ("Ramp", "ConcavePolygonShape3D_partial"),
+        ("Sign", "StandardMaterial3D_short"),
+    ] {
+        assert!(matches!(
+            plan(name),
+            Err(LoaderError::InvalidResource { resource: invalid, .. }) if invalid == resource
+        ));
+    }
+}
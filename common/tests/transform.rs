@@ -0,0 +1,53 @@
+use common::transform::CoordinateSystem;
+
+/// Multiplies a column-major 4x4 matrix with a direction.
+fn transform_direction(matrix: &[f32; 16], direction: [f32; 3]) -> [f32; 3] {
+    let mut result = [0.0; 3];
+    for (row, value) in result.iter_mut().enumerate() {
+        *value = (0..3)
+            .map(|col| matrix[col * 4 + row] * direction[col])
+            .sum();
+    }
+    result
+}
+
+/// Determinant of the upper-left 3x3 part of a column-major 4x4 matrix.
+fn determinant(m: &[f32; 16]) -> f32 {
+    let at = |row: usize, col: usize| m[col * 4 + row];
+    at(0, 0) * (at(1, 1) * at(2, 2) - at(1, 2) * at(2, 1))
+        - at(0, 1) * (at(1, 0) * at(2, 2) - at(1, 2) * at(2, 0))
+        + at(0, 2) * (at(1, 0) * at(2, 1) - at(1, 1) * at(2, 0))
+}
+
+#[test]
+fn y_up_is_the_identity() {
+    let matrix = CoordinateSystem::YUp.to_y_up_matrix();
+    for (i, value) in matrix.iter().enumerate() {
+        let expected = if i % 5 == 0 { 1.0 } else { 0.0 };
+        assert_eq!(*value, expected, "element {}", i);
+    }
+}
+
+#[test]
+fn z_up_maps_up_to_y() {
+    let matrix = CoordinateSystem::ZUp.to_y_up_matrix();
+    assert_eq!(
+        transform_direction(&matrix, [0.0, 0.0, 1.0]),
+        [0.0, 1.0, 0.0]
+    );
+    assert_eq!(
+        transform_direction(&matrix, [1.0, 0.0, 0.0]),
+        [1.0, 0.0, 0.0]
+    );
+    assert_eq!(
+        transform_direction(&matrix, [0.0, 1.0, 0.0]),
+        [0.0, 0.0, -1.0]
+    );
+}
+
+#[test]
+fn z_up_keeps_the_handedness() {
+    let matrix = CoordinateSystem::ZUp.to_y_up_matrix();
+    assert_eq!(determinant(&matrix), 1.0);
+    assert_eq!(&matrix[12..], &[0.0, 0.0, 0.0, 1.0]);
+}
@@ -0,0 +1,72 @@
+//! Loads a scene and simulates it without rendering, printing where the bodies come to rest.
+//!
+//! Run with the path of an exported scene, or without one for a built-in scene:
+//! `cargo run -p rapier_godot_scene_loader --example headless -- path/to/world.json`
+
+#[cfg(feature = "rapier3d-f64")]
+extern crate rapier3d_f64 as rapier3d;
+
+use common::builder::{translation, SceneWorldBuilder};
+use rapier3d::na::Vector3;
+use rapier_godot_scene_loader::{
+    load_scene_world_file, load_world_to_rapier, step_world, RapierWorld, SceneWorld,
+    SpawnedWorldEntityData,
+};
+
+/// Two seconds at rapier's default time step.
+const STEPS: usize = 120;
+
+fn main() {
+    let world = match std::env::args().nth(1) {
+        Some(path) => load_scene_world_file(&path).expect("scene file should load"),
+        None => default_world(),
+    };
+
+    let RapierWorld {
+        mut bodies,
+        mut colliders,
+        mut islands,
+        mut impulse_joints,
+        entities,
+        errors,
+        ..
+    } = load_world_to_rapier(&world, None).expect("scene should have valid transforms");
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+
+    step_world(
+        &mut bodies,
+        &mut colliders,
+        &mut islands,
+        &mut impulse_joints,
+        Vector3::new(0.0, -9.81, 0.0),
+        STEPS,
+    );
+
+    let mut names: Vec<_> = entities.keys().collect();
+    names.sort();
+    for name in names {
+        if let SpawnedWorldEntityData::PhysicsBody((handle, _)) = entities[name].data {
+            let body = &bodies[handle];
+            println!(
+                "{}: position {}, sleeping {}",
+                name,
+                body.translation(),
+                body.is_sleeping()
+            );
+        }
+    }
+}
+
+/// A ball and a crate dropped onto the ground.
+fn default_world() -> SceneWorld {
+    SceneWorldBuilder::new()
+        .add_static_body("Ground", translation([0.0, -0.5, 0.0]))
+        .with_box_collider([20.0, 1.0, 20.0])
+        .add_rigid_body("Ball", translation([0.0, 3.0, 0.0]))
+        .with_sphere_collider(0.5)
+        .add_rigid_body("Crate", translation([2.0, 5.0, 0.0]))
+        .with_box_collider([1.0, 1.0, 1.0])
+        .build()
+}
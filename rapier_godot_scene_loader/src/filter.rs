@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use common::{bounds::Aabb, WorldEntity};
+use rapier3d::{math::Real, na::Matrix4};
+
+use crate::{get_entity_transform, to_real};
+
+type Filter = dyn Fn(&WorldEntity, &Matrix4<Real>) -> bool + Send + Sync;
+
+/// Decides which entities of a scene are spawned, given the entity and its world transform.
+/// An entity that is filtered out while some of its descendants pass is returned as a
+/// [Node](crate::SpawnedWorldEntityData::Node) placeholder, with nothing spawned in rapier,
+/// so that the descendants can still refer to it as their parent.
+/// Entities without any passing descendants are skipped along with their whole subtree.
+///
+/// Transforms in the scene are relative to the parent, and the transform passed to the filter
+/// is already composed with those of all ancestors, whether they pass or not, so filtering never
+/// moves anything. Collision shapes whose body is filtered out have no body to be attached to
+/// though, so they become fixed colliders placed in world space.
+#[derive(Clone)]
+pub struct SpawnFilter(Arc<Filter>);
+
+impl SpawnFilter {
+    pub fn new(
+        filter: impl Fn(&WorldEntity, &Matrix4<Real>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(filter))
+    }
+
+    /// Decides by the entity alone, e.g. its type or name.
+    pub fn by_entity(filter: impl Fn(&WorldEntity) -> bool + Send + Sync + 'static) -> Self {
+        Self::new(move |entity, _| filter(entity))
+    }
+
+    /// Only spawns entities whose origin is inside `region`, e.g. to stream a large scene
+    /// in chunks.
+    pub fn region(region: Aabb) -> Self {
+        Self::new(move |_, transform| {
+            (0..3).all(|i| {
+                let position = transform[(i, 3)];
+                to_real(region.min[i]) <= position && position <= to_real(region.max[i])
+            })
+        })
+    }
+
+    pub fn includes(&self, entity: &WorldEntity, transform: &Matrix4<Real>) -> bool {
+        (self.0)(entity, transform)
+    }
+
+    /// Whether any descendant of the entity placed at `transform` passes the filter.
+    /// Descendants with a malformed transform can't be placed, so they never pass.
+    pub fn includes_descendants(&self, entity: &WorldEntity, transform: &Matrix4<Real>) -> bool {
+        entity.children.iter().flatten().any(|child| {
+            let local = match get_entity_transform(child) {
+                Ok(local) => local,
+                Err(_) => return false,
+            };
+            let transform = transform * local;
+
+            self.includes(child, &transform) || self.includes_descendants(child, &transform)
+        })
+    }
+}
+
+impl std::fmt::Debug for SpawnFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SpawnFilter")
+    }
+}
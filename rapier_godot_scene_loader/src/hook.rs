@@ -0,0 +1,38 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use common::WorldEntity;
+use rapier3d::{dynamics::RigidBodySet, geometry::ColliderSet};
+
+use crate::SpawnedWorldEntityData;
+
+type Hook = dyn FnMut(&WorldEntity, SpawnRef<'_>) + Send;
+
+/// Called right after each entity is spawned, e.g. to set up game-specific state based on
+/// the metadata. Children are spawned after their parent, so the hook sees the entities in
+/// the order of the scene tree. Entities that failed to spawn are skipped.
+#[derive(Clone)]
+pub struct SpawnHook(Arc<Mutex<Hook>>);
+
+impl SpawnHook {
+    pub fn new(hook: impl FnMut(&WorldEntity, SpawnRef<'_>) + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(hook)))
+    }
+
+    pub fn call(&self, entity: &WorldEntity, spawned: SpawnRef<'_>) {
+        let mut hook = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        hook(entity, spawned)
+    }
+}
+
+impl std::fmt::Debug for SpawnHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SpawnHook")
+    }
+}
+
+/// The handles spawned for a [WorldEntity], passed to the [SpawnHook].
+pub struct SpawnRef<'a> {
+    pub data: &'a SpawnedWorldEntityData,
+    pub bodies: &'a mut RigidBodySet,
+    pub colliders: &'a mut ColliderSet,
+}
@@ -1,27 +1,76 @@
-use std::collections::HashMap;
+#[cfg(not(any(feature = "rapier3d", feature = "rapier3d-f64")))]
+compile_error!("either the rapier3d or the rapier3d-f64 feature has to be enabled");
+#[cfg(all(feature = "rapier3d", feature = "rapier3d-f64"))]
+compile_error!("the rapier3d and rapier3d-f64 features can't be enabled together");
 
+#[cfg(feature = "rapier3d-f64")]
+extern crate rapier3d_f64 as rapier3d;
+
+use std::collections::{HashMap, HashSet};
+
+use log::{debug, error, warn};
+
+pub mod filter;
+pub mod hook;
+pub mod link;
+pub mod mesh;
+mod options;
+mod step;
+pub use filter::SpawnFilter;
+pub use hook::{SpawnHook, SpawnRef};
+pub use link::{LinkContext, Linker};
+pub use mesh::{MeshLoader, TriMeshData};
+pub use options::RapierLoadOptions;
+pub use step::step_world;
+
+pub use common::error::LoaderError;
+#[cfg(feature = "tokio")]
+pub use common::load_scene_world_file_async;
+#[cfg(feature = "zip")]
+pub use common::load_scene_world_from_archive;
+pub use common::{
+    bounds::Aabb,
+    load_scene_world_file,
+    plan::{plan_scene, SpawnPlanEntry},
+    registry::ParserRegistry,
+    transform::{CoordinateSystem, TransformError},
+    try_load_scene_world_file, SceneFileError, SceneWorld, SceneWorldJson,
+};
 use common::{
-    entities::physics::CollisionShapeData, get_or_return_val, EntityData, ResourceData,
-    WorldEntity, WorldResource,
+    entities::{
+        physics::{BodyActivation, ColliderMaterial, CollisionShapeData, MassOverride},
+        render::{GridMapData, MeshInstanceData},
+    },
+    get_or_continue, get_or_return_val,
+    metadata::Metadata,
+    plan::{plan_entity, SpawnPlan},
+    transform::to_matrix_columns,
+    EntityData, ResourceData, WorldEntity, WorldEntityJson, WorldResource,
 };
-pub use common::{load_scene_world_file, SceneWorld};
 use rapier3d::{
-    dynamics::{IslandManager, RigidBodyBuilder, RigidBodyHandle, RigidBodySet, RigidBodyType},
+    dynamics::{
+        GenericJoint, ImpulseJointHandle, ImpulseJointSet, IslandManager, LockedAxes,
+        MassProperties, MultibodyJointSet, RevoluteJointBuilder, RigidBody, RigidBodyBuilder,
+        RigidBodyHandle, RigidBodySet, RigidBodyType, SphericalJointBuilder,
+    },
     geometry::{ActiveCollisionTypes, Collider, ColliderBuilder, ColliderHandle, ColliderSet},
-    na::{Isometry3, Matrix3, Matrix4, Point3, Rotation3, UnitQuaternion, Vector3, Vector4},
+    math::Real,
+    na::{
+        DMatrix, Isometry3, Matrix3, Matrix4, Point3, Rotation3, Unit, UnitQuaternion, Vector3,
+        Vector4,
+    },
     pipeline::ActiveEvents,
 };
-use serde_json::Value;
 
 #[derive(Clone, Debug)]
 pub struct NodeTransform {
-    pub matrix: Matrix4<f32>,
+    pub matrix: Matrix4<Real>,
 
-    pub rotation: UnitQuaternion<f32>,
-    pub translation: Vector3<f32>,
+    pub rotation: UnitQuaternion<Real>,
+    pub translation: Vector3<Real>,
 }
 
-impl From<NodeTransform> for Isometry3<f32> {
+impl From<NodeTransform> for Isometry3<Real> {
     fn from(transform: NodeTransform) -> Self {
         return Isometry3 {
             translation: transform.translation.into(),
@@ -30,7 +79,7 @@ impl From<NodeTransform> for Isometry3<f32> {
     }
 }
 
-impl From<&NodeTransform> for Isometry3<f32> {
+impl From<&NodeTransform> for Isometry3<Real> {
     fn from(transform: &NodeTransform) -> Self {
         return Isometry3 {
             translation: transform.translation.into(),
@@ -43,25 +92,231 @@ impl From<&NodeTransform> for Isometry3<f32> {
 pub enum SpawnedWorldEntityData {
     PhysicsBody((RigidBodyHandle, RigidBodyType)),
     Collider(ColliderHandle),
+    /// Area node, whose child collision shapes are spawned as sensors.
+    Area,
+
+    /// Grid map with the colliders of its cells.
+    GridMap(Vec<ColliderHandle>),
+
+    /// Joint connecting two bodies of the scene, or a body and the fixed body that a one-sided
+    /// joint is anchored to, which is removed along with the world.
+    Joint((ImpulseJointHandle, Option<RigidBodyHandle>)),
     Node,
 }
 
+impl SpawnedWorldEntityData {
+    /// Colliders spawned for the entity, one per cell for grid maps. Shapes merged into the
+    /// compound collider of their body all share that collider.
+    pub fn colliders(&self) -> &[ColliderHandle] {
+        match self {
+            SpawnedWorldEntityData::Collider(handle) => std::slice::from_ref(handle),
+            SpawnedWorldEntityData::GridMap(cells) => cells,
+            _ => &[],
+        }
+    }
+}
+
+/// Colliders of the loaded entities along with the name of the entity they were spawned from.
+/// Grid maps appear once for every collider of their cells.
+pub fn colliders_by_name(
+    entities: &HashMap<String, SpawnedWorldEntity>,
+) -> impl Iterator<Item = (&str, ColliderHandle)> {
+    entities.iter().flat_map(|(name, entity)| {
+        entity
+            .data
+            .colliders()
+            .iter()
+            .map(move |handle| (name.as_str(), *handle))
+    })
+}
+
 pub struct SpawnedWorldEntity {
     pub entity_type: String,
+
+    /// Name of the parent entity in the scene, if any.
+    pub parent: Option<String>,
     pub data: SpawnedWorldEntityData,
-    pub transform: NodeTransform,
-    pub metadata: HashMap<String, Value>,
+
+    /// Transform relative to the parent entity, as written in the scene.
+    pub local_transform: NodeTransform,
+
+    /// Transform in the rapier world at load time, including the root transform of the options.
+    pub world_transform: NodeTransform,
+    pub metadata: Metadata,
+
+    /// Linear velocity of a kinematic body, see [step_kinematic_bodies].
+    pub linear_velocity: Option<Vector3<Real>>,
+}
+
+/// Reverse lookup from rapier handles to the names of the entities they were spawned from,
+/// e.g. to find out which entity fired a collision event.
+#[derive(Default, Debug, Clone)]
+pub struct EntityHandles {
+    pub colliders: HashMap<ColliderHandle, String>,
+    pub bodies: HashMap<RigidBodyHandle, String>,
+}
+
+impl EntityHandles {
+    pub fn from_entities(entities: &HashMap<String, SpawnedWorldEntity>) -> Self {
+        let mut handles = Self::default();
+        for (name, entity) in entities {
+            match &entity.data {
+                SpawnedWorldEntityData::PhysicsBody((handle, _)) => {
+                    handles.bodies.insert(*handle, name.clone());
+                }
+                data => {
+                    for handle in data.colliders() {
+                        handles.colliders.insert(*handle, name.clone());
+                    }
+                }
+            }
+        }
+
+        return handles;
+    }
+
+    pub fn collider_name(&self, handle: ColliderHandle) -> Option<&str> {
+        self.colliders.get(&handle).map(|x| x.as_str())
+    }
+
+    pub fn body_name(&self, handle: RigidBodyHandle) -> Option<&str> {
+        self.bodies.get(&handle).map(|x| x.as_str())
+    }
+}
+
+/// Counts of the bodies and colliders of a loaded scene, e.g. for a HUD or a performance budget.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldStats {
+    pub dynamic_bodies: usize,
+    pub static_bodies: usize,
+    pub kinematic_bodies: usize,
+
+    /// Colliders including sensors, where a compound collider shared by several shapes
+    /// counts once.
+    pub colliders: usize,
+    pub sensors: usize,
+}
+
+impl WorldStats {
+    /// Looks up the colliders of the entities in `colliders` to tell the sensors apart.
+    /// Colliders that were removed from the set since loading aren't counted.
+    pub fn from_entities(
+        entities: &HashMap<String, SpawnedWorldEntity>,
+        colliders: &ColliderSet,
+    ) -> Self {
+        let mut stats = Self::default();
+        let mut counted = HashSet::new();
+        for entity in entities.values() {
+            match &entity.data {
+                SpawnedWorldEntityData::PhysicsBody((_, body_type)) => match body_type {
+                    RigidBodyType::Dynamic => stats.dynamic_bodies += 1,
+                    RigidBodyType::Fixed => stats.static_bodies += 1,
+                    RigidBodyType::KinematicPositionBased
+                    | RigidBodyType::KinematicVelocityBased => stats.kinematic_bodies += 1,
+                },
+                data => {
+                    for handle in data.colliders() {
+                        let collider = get_or_continue!(colliders.get(*handle));
+                        if !counted.insert(*handle) {
+                            continue;
+                        }
+
+                        stats.colliders += 1;
+                        if collider.is_sensor() {
+                            stats.sensors += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        return stats;
+    }
+}
+
+/// New rapier sets holding a world loaded with [load_world_to_rapier], along with its entities.
+pub struct RapierWorld {
+    pub bodies: RigidBodySet,
+    pub colliders: ColliderSet,
+    pub islands: IslandManager,
+    pub impulse_joints: ImpulseJointSet,
+    pub entities: HashMap<String, SpawnedWorldEntity>,
+    pub handles: EntityHandles,
+
+    /// Entities that were skipped because of missing or invalid resources.
+    pub errors: Vec<LoaderError>,
+}
+
+/// Spawned entities stored by index, where `index + 1` is packed into the `user_data` of their
+/// rapier body or collider, so that the entity can be recovered from a handle without a lookup.
+/// A `user_data` of 0 means that the body or collider wasn't spawned from the scene.
+pub struct IndexedEntities {
+    pub entities: Vec<(String, SpawnedWorldEntity)>,
+}
+
+impl IndexedEntities {
+    /// Takes ownership of the loaded entities and writes their indices into the rapier sets.
+    pub fn new(
+        entities: HashMap<String, SpawnedWorldEntity>,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+    ) -> Self {
+        let entities: Vec<(String, SpawnedWorldEntity)> = entities.into_iter().collect();
+
+        for (index, (_, entity)) in entities.iter().enumerate() {
+            let user_data = index as u128 + 1;
+            match &entity.data {
+                SpawnedWorldEntityData::PhysicsBody((handle, _)) => {
+                    if let Some(body) = bodies.get_mut(*handle) {
+                        body.user_data = user_data;
+                    }
+                }
+                data => {
+                    for handle in data.colliders() {
+                        if let Some(collider) = colliders.get_mut(*handle) {
+                            collider.user_data = user_data;
+                        }
+                    }
+                }
+            }
+        }
+
+        return Self { entities };
+    }
+
+    pub fn get(&self, user_data: u128) -> Option<(&str, &SpawnedWorldEntity)> {
+        let index = (user_data as usize).checked_sub(1)?;
+        self.entities
+            .get(index)
+            .map(|(name, entity)| (name.as_str(), entity))
+    }
+
+    pub fn get_by_body(&self, body: &RigidBody) -> Option<(&str, &SpawnedWorldEntity)> {
+        self.get(body.user_data)
+    }
+
+    pub fn get_by_collider(&self, collider: &Collider) -> Option<(&str, &SpawnedWorldEntity)> {
+        self.get(collider.user_data)
+    }
 }
 
 impl NodeTransform {
-    pub fn from_matrix(matrix: &Matrix4<f32>) -> Self {
-        let last_column: Vector4<f32> = matrix.column(3).into();
-        let translation: Vector3<f32> =
+    pub fn from_matrix(matrix: &Matrix4<Real>) -> Self {
+        let last_column: Vector4<Real> = matrix.column(3).into();
+        let translation: Vector3<Real> =
             Vector3::new(last_column[0], last_column[1], last_column[2]);
 
-        let rotation_view: Matrix3<f32> = matrix.fixed_view::<3, 3>(0, 0).into();
-        let rotation =
-            UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix(&rotation_view));
+        // The basis carries the scale as well, which has to be divided out of the columns
+        // before the rotation can be extracted. A mirrored basis is flipped back like Godot
+        // does, so the mirror ends up as a negative scale instead of in the rotation
+        let mut basis: Matrix3<Real> = matrix.fixed_view::<3, 3>(0, 0).into();
+        for mut column in basis.column_iter_mut() {
+            column.normalize_mut();
+        }
+        if basis.determinant() < 0.0 {
+            basis = -basis;
+        }
+        let rotation = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix(&basis));
 
         return Self {
             matrix: matrix.clone(),
@@ -69,6 +324,57 @@ impl NodeTransform {
             rotation,
         };
     }
+
+    /// Reads a serialized transform of the scene, either the full 16 floats of a column-major
+    /// matrix or Godot's 12 floats of the basis columns followed by the origin.
+    pub fn from_godot_transform(transform: &[f32]) -> Result<Self, TransformError> {
+        let columns = to_matrix_columns::<Real>(transform)?;
+        return Ok(Self::from_matrix(&Matrix4::from_column_slice(&columns)));
+    }
+
+    /// Builds the transform that scales, then rotates, then translates.
+    pub fn from_parts(
+        translation: Vector3<Real>,
+        rotation: UnitQuaternion<Real>,
+        scale: Vector3<Real>,
+    ) -> Self {
+        let matrix = Matrix4::new_translation(&translation)
+            * rotation.to_homogeneous()
+            * Matrix4::new_nonuniform_scaling(&scale);
+
+        return Self {
+            matrix,
+            translation,
+            rotation,
+        };
+    }
+
+    /// Scale along each local axis, which is the length of the basis columns.
+    /// A mirrored transform has all three components negative, matching [NodeTransform::rotation].
+    pub fn scale(&self) -> Vector3<Real> {
+        let basis = self.matrix.fixed_view::<3, 3>(0, 0);
+        let scale = Vector3::new(
+            basis.column(0).norm(),
+            basis.column(1).norm(),
+            basis.column(2).norm(),
+        );
+
+        return if basis.determinant() < 0.0 {
+            -scale
+        } else {
+            scale
+        };
+    }
+
+    /// Whether the transform mirrors the node, i.e. its basis has a negative determinant.
+    pub fn is_mirrored(&self) -> bool {
+        return self.matrix.fixed_view::<3, 3>(0, 0).determinant() < 0.0;
+    }
+
+    /// Roll, pitch and yaw of the rotation in radians, see [UnitQuaternion::euler_angles].
+    pub fn euler_angles(&self) -> (Real, Real, Real) {
+        return self.rotation.euler_angles();
+    }
 }
 
 impl Default for NodeTransform {
@@ -81,242 +387,1099 @@ impl Default for NodeTransform {
     }
 }
 
+/// Loads a [SceneWorld] into new rapier sets.
+/// Fails if any entity has a malformed transform, while entities referencing missing or invalid
+/// resources are skipped and returned as [LoaderError]s.
 pub fn load_world_to_rapier(
     world: &SceneWorld,
-    transform: Option<Matrix4<f32>>,
-) -> (
-    RigidBodySet,
-    ColliderSet,
-    IslandManager,
-    HashMap<String, SpawnedWorldEntity>,
-) {
+    transform: Option<Matrix4<Real>>,
+) -> Result<RapierWorld, TransformError> {
     let mut bodies = RigidBodySet::new();
     let mut colliders = ColliderSet::new();
     let mut islands = IslandManager::new();
+    let mut impulse_joints = ImpulseJointSet::new();
+
+    let (entities, errors) = load_world_to_rapier_with_prefix(
+        world,
+        transform,
+        "",
+        &mut bodies,
+        &mut colliders,
+        &mut islands,
+        &mut impulse_joints,
+    )?;
+
+    let handles = EntityHandles::from_entities(&entities);
+
+    return Ok(RapierWorld {
+        bodies,
+        colliders,
+        islands,
+        impulse_joints,
+        entities,
+        handles,
+        errors,
+    });
+}
+
+/// Loads a [SceneWorld] into caller-owned rapier sets, e.g. the persistent physics world of
+/// a server, instead of creating new ones like [load_world_to_rapier].
+pub fn load_world_into_rapier(
+    world: &SceneWorld,
+    transform: Option<Matrix4<Real>>,
+
+    bodies: &mut RigidBodySet,
+    colliders: &mut ColliderSet,
+    islands: &mut IslandManager,
+    impulse_joints: &mut ImpulseJointSet,
+) -> Result<(HashMap<String, SpawnedWorldEntity>, Vec<LoaderError>), TransformError> {
+    load_world_to_rapier_with_prefix(
+        world,
+        transform,
+        "",
+        bodies,
+        colliders,
+        islands,
+        impulse_joints,
+    )
+}
+
+/// Same as [load_world_into_rapier], but only spawns the entities whose origin is inside `region`,
+/// see [SpawnFilter].
+pub fn load_world_into_rapier_filtered(
+    world: &SceneWorld,
+    region: Aabb,
+
+    bodies: &mut RigidBodySet,
+    colliders: &mut ColliderSet,
+    islands: &mut IslandManager,
+    impulse_joints: &mut ImpulseJointSet,
+) -> Result<(HashMap<String, SpawnedWorldEntity>, Vec<LoaderError>), TransformError> {
+    let options = RapierLoadOptions {
+        filter: Some(SpawnFilter::region(region)),
+        ..Default::default()
+    };
+
+    load_world_to_rapier_with(world, &options, bodies, colliders, islands, impulse_joints)
+}
+
+/// Loads a [SceneWorld] into existing rapier sets, so that several worlds can be composed into
+/// a single simulation. The names of the returned entities are prefixed with `prefix`.
+pub fn load_world_to_rapier_with_prefix(
+    world: &SceneWorld,
+    transform: Option<Matrix4<Real>>,
+    prefix: &str,
+
+    bodies: &mut RigidBodySet,
+    colliders: &mut ColliderSet,
+    islands: &mut IslandManager,
+    impulse_joints: &mut ImpulseJointSet,
+) -> Result<(HashMap<String, SpawnedWorldEntity>, Vec<LoaderError>), TransformError> {
+    let options = RapierLoadOptions {
+        transform,
+        prefix: prefix.to_owned(),
+        ..Default::default()
+    };
+
+    load_world_to_rapier_with(world, &options, bodies, colliders, islands, impulse_joints)
+}
+
+/// Loads a [SceneWorld] into existing rapier sets, configured with [RapierLoadOptions].
+pub fn load_world_to_rapier_with(
+    world: &SceneWorld,
+    options: &RapierLoadOptions,
+
+    bodies: &mut RigidBodySet,
+    colliders: &mut ColliderSet,
+    islands: &mut IslandManager,
+    impulse_joints: &mut ImpulseJointSet,
+) -> Result<(HashMap<String, SpawnedWorldEntity>, Vec<LoaderError>), TransformError> {
     let mut entities: HashMap<String, SpawnedWorldEntity> = HashMap::new();
+    for err in &world.errors {
+        warn!("skipping {}", err);
+    }
+    let mut errors = world.errors.clone();
 
+    // Convert the scene to Y-up before placing it with the root transform
+    let basis = options.coordinate_system.to_y_up_matrix().map(to_real);
+    let basis = Matrix4::from_column_slice(&basis);
+    let scale = Matrix4::new_scaling(options.scene_scale);
+    let root_transform = options.transform.unwrap_or(Matrix4::identity()) * basis * scale;
+
+    let mut context = SpawnContext {
+        bodies: &mut *bodies,
+        colliders: &mut *colliders,
+        resources: &world.resources,
+        entities: &mut entities,
+        errors: &mut errors,
+        options,
+    };
     for entity in &world.entities {
         spawn_entity(
-            &entity,
-            transform.unwrap_or(Matrix4::identity()),
+            entity,
+            root_transform,
             None,
-            &mut bodies,
-            &mut colliders,
-            &mut islands,
-            &world.resources,
-            &mut entities,
-        );
+            None,
+            None,
+            islands,
+            &mut context,
+        )?;
     }
 
-    return (bodies, colliders, islands, entities);
+    // Second phase, resolving references by name now that all entities are spawned
+    let mut context = LinkContext {
+        entities: &mut entities,
+        bodies,
+        colliders,
+        impulse_joints,
+    };
+    let joints = Linker::new(link_joint);
+    for linker in std::iter::once(&joints).chain(&options.linkers) {
+        for (entity, _, _) in world.iter_entities() {
+            if let Err(err) = linker.link(entity, &mut context) {
+                warn!("unable to link {}: {}", entity.name, err);
+                errors.push(err);
+            }
+        }
+    }
+
+    let prefix = &options.prefix;
+    if prefix.is_empty() {
+        return Ok((entities, errors));
+    }
+
+    let entities = entities
+        .into_iter()
+        .map(|(name, mut entity)| {
+            entity.parent = entity.parent.map(|parent| format!("{}{}", prefix, parent));
+            (format!("{}{}", prefix, name), entity)
+        })
+        .collect();
+
+    return Ok((entities, errors));
 }
 
-fn get_entity_transform(entity: &WorldEntity) -> Option<Matrix4<f32>> {
-    let data: Option<&Vec<f32>> = match &entity.data {
-        EntityData::StaticBody3D(body) => Some(&body.transform),
-        EntityData::RigidBody3D(body) => Some(&body.transform),
-        EntityData::KinematicBody3D(body) => Some(&body.transform),
-        EntityData::CollisionShape3D(shape) => Some(&shape.transform),
-        EntityData::Node3D(body) => Some(&body.transform),
-        EntityData::ModelScene(body) => Some(&body.transform),
-        _ => None,
+/// Built-in linker connecting the bodies referenced by joint entities.
+fn link_joint(entity: &WorldEntity, context: &mut LinkContext<'_>) -> Result<(), LoaderError> {
+    let spawned = get_or_return_val!(context.entities.get(&entity.name), Ok(()));
+    let frame = Isometry3::from(&spawned.world_transform);
+
+    let data = get_or_return_val!(create_joint(entity, &frame, context)?, Ok(()));
+    if let Some(spawned) = context.entities.get_mut(&entity.name) {
+        spawned.data = data;
+    }
+
+    return Ok(());
+}
+
+/// Inserts the rapier joint of a joint entity placed at `frame` in world space.
+/// Returns `None` for other entities.
+fn create_joint(
+    entity: &WorldEntity,
+    frame: &Isometry3<Real>,
+    context: &mut LinkContext<'_>,
+) -> Result<Option<SpawnedWorldEntityData>, LoaderError> {
+    let (node_a, node_b) = match &entity.data {
+        EntityData::PinJoint3D(joint) => (&joint.node_a, &joint.node_b),
+        EntityData::HingeJoint3D(joint) => (&joint.node_a, &joint.node_b),
+        _ => return Ok(None),
+    };
+    if node_a.is_none() && node_b.is_none() {
+        return Err(LoaderError::InvalidJoint {
+            joint: entity.name.clone(),
+            reason: "doesn't connect any body".to_owned(),
+        });
+    }
+
+    // The frame of the joint in the local space of each body
+    let side = |name: &Option<String>| {
+        name.as_deref()
+            .map(|name| joint_body(entity, name, frame, context.bodies, context.entities))
+            .transpose()
+    };
+    let (side_a, side_b) = (side(node_a)?, side(node_b)?);
+
+    // Godot pins the body in place when one side is unset, so it's attached to a fixed body
+    // at the joint instead
+    let (anchor, (body_a, frame_a), (body_b, frame_b)) = match (side_a, side_b) {
+        (Some(side_a), Some(side_b)) => (None, side_a, side_b),
+        (side_a, side_b) => {
+            let fixed = RigidBodyBuilder::fixed().position(*frame).build();
+            let fixed = (context.bodies.insert(fixed), Isometry3::identity());
+            (
+                Some(fixed.0),
+                side_a.unwrap_or(fixed),
+                side_b.unwrap_or(fixed),
+            )
+        }
     };
+    let anchor_a = frame_a.translation.vector.into();
+    let anchor_b = frame_b.translation.vector.into();
+
+    let data = match &entity.data {
+        EntityData::HingeJoint3D(joint) => {
+            let mut builder = RevoluteJointBuilder::new(frame_a.rotation * Vector3::z_axis())
+                .local_anchor1(anchor_a)
+                .local_anchor2(anchor_b);
+            if let Some(limit) = joint.angular_limit {
+                builder = builder.limits([to_real(limit[0]), to_real(limit[1])]);
+            }
+
+            let mut data: GenericJoint = builder.build().into();
+            data.set_local_axis2(frame_b.rotation * Vector3::z_axis());
+            data
+        }
+        _ => SphericalJointBuilder::new()
+            .local_anchor1(anchor_a)
+            .local_anchor2(anchor_b)
+            .build()
+            .into(),
+    };
+
+    let handle = context.impulse_joints.insert(body_a, body_b, data, true);
+    return Ok(Some(SpawnedWorldEntityData::Joint((handle, anchor))));
+}
+
+/// The spawned body named `name` of a joint, along with the joint `frame` relative to it.
+fn joint_body(
+    joint: &WorldEntity,
+    name: &str,
+    frame: &Isometry3<Real>,
+    bodies: &RigidBodySet,
+    entities: &HashMap<String, SpawnedWorldEntity>,
+) -> Result<(RigidBodyHandle, Isometry3<Real>), LoaderError> {
+    let invalid = |reason: String| LoaderError::InvalidJoint {
+        joint: joint.name.clone(),
+        reason,
+    };
+
+    let handle = match entities.get(name).map(|x| &x.data) {
+        Some(SpawnedWorldEntityData::PhysicsBody((handle, _))) => *handle,
+        _ => {
+            return Err(invalid(format!(
+                "references {}, which is not a spawned body",
+                name
+            )))
+        }
+    };
+
+    let body = bodies
+        .get(handle)
+        .ok_or_else(|| invalid(format!("references removed body {}", name)))?;
+    return Ok((handle, body.position().inverse() * frame));
+}
 
-    return data.and_then(|x| {
-        return Some(Matrix4::from_column_slice(&x));
+/// Removes all bodies and colliders of a world previously loaded with [load_world_to_rapier].
+/// Useful when several worlds were loaded into the same sets.
+pub fn unload_world_from_rapier(
+    entities: &HashMap<String, SpawnedWorldEntity>,
+    bodies: &mut RigidBodySet,
+    colliders: &mut ColliderSet,
+    islands: &mut IslandManager,
+    impulse_joints: &mut ImpulseJointSet,
+    multibody_joints: &mut MultibodyJointSet,
+) {
+    for entity in entities.values() {
+        let body = match &entity.data {
+            SpawnedWorldEntityData::PhysicsBody((handle, _)) => handle,
+            SpawnedWorldEntityData::Joint((_, Some(anchor))) => anchor,
+            _ => continue,
+        };
+        bodies.remove(
+            *body,
+            islands,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            true,
+        );
+    }
+
+    // Colliders parented to a body were already removed along with it
+    for (_, handle) in colliders_by_name(entities) {
+        colliders.remove(handle, islands, bodies, false);
+    }
+}
+
+/// Writes the current positions of the rapier bodies back into a copy of the scene that was
+/// loaded, e.g. to save the edits of a level editor. `entities` must be the unprefixed entities
+/// returned by [load_world_to_rapier]. Everything else, including the resources, is carried over.
+/// Bodies are moved rigidly, so any scale in their original transform is kept.
+pub fn export_world_to_json(
+    world: &SceneWorldJson,
+    entities: &HashMap<String, SpawnedWorldEntity>,
+    bodies: &RigidBodySet,
+) -> SceneWorldJson {
+    let mut world = world.clone();
+    for entity in &mut world.entities {
+        export_entity_transform(entity, Isometry3::identity(), entities, bodies);
+    }
+
+    return world;
+}
+
+/// `parent_delta` is how far the closest moved ancestor was moved in world space since loading.
+fn export_entity_transform(
+    entity: &mut WorldEntityJson,
+    parent_delta: Isometry3<Real>,
+    entities: &HashMap<String, SpawnedWorldEntity>,
+    bodies: &RigidBodySet,
+) {
+    let mut delta = parent_delta;
+
+    let spawned = entities.get(&entity.name);
+    let body = spawned.and_then(|spawned| match &spawned.data {
+        SpawnedWorldEntityData::PhysicsBody((handle, _)) => bodies.get(*handle),
+        _ => None,
     });
+
+    if let (Some(spawned), Some(body)) = (spawned, body) {
+        delta = body.position() * Isometry3::from(&spawned.world_transform).inverse();
+
+        // Move the local transform by the change relative to the parent, in the parent's space
+        let local = entity
+            .data
+            .get("transform")
+            .and_then(|x| serde_json::from_value::<Vec<f32>>(x.clone()).ok())
+            .and_then(|x| NodeTransform::from_godot_transform(&x).ok())
+            .map(|x| x.matrix);
+
+        if let Some(local) = local {
+            let world = spawned.world_transform.matrix;
+            let change = (parent_delta.inverse() * delta).to_homogeneous();
+            let inverse_world = world.try_inverse().unwrap_or(Matrix4::identity());
+
+            let new_local = local * inverse_world * change * world;
+            if let Some(data) = entity.data.as_object_mut() {
+                data.insert(
+                    "transform".to_owned(),
+                    serde_json::json!(new_local.as_slice()),
+                );
+            }
+        }
+    }
+
+    if let Some(children) = &mut entity.children {
+        for child in children {
+            export_entity_transform(child, delta, entities, bodies);
+        }
+    }
+}
+
+/// Moves the kinematic bodies that have a stored linear velocity by it over `dt` seconds,
+/// e.g. for moving platforms. Should be called once before every physics step, with the time
+/// step of the pipeline. These bodies are spawned position based, so that they only move here.
+pub fn step_kinematic_bodies(
+    entities: &HashMap<String, SpawnedWorldEntity>,
+    bodies: &mut RigidBodySet,
+    dt: Real,
+) {
+    for entity in entities.values() {
+        let velocity = get_or_continue!(entity.linear_velocity);
+        let handle = match &entity.data {
+            SpawnedWorldEntityData::PhysicsBody((
+                handle,
+                RigidBodyType::KinematicPositionBased,
+            )) => *handle,
+            _ => continue,
+        };
+
+        let body = get_or_continue!(bodies.get_mut(handle));
+        let mut position = *body.position();
+        position.translation.vector += velocity * dt;
+
+        body.set_next_kinematic_position(position);
+    }
+}
+
+/// Transform of `entity` relative to its parent, see [EntityData::transform].
+fn get_entity_transform(entity: &WorldEntity) -> Result<Matrix4<Real>, TransformError> {
+    return NodeTransform::from_godot_transform(entity.data.transform())
+        .map(|transform| transform.matrix)
+        .map_err(|err| err.for_entity(&entity.name));
+}
+
+/// What the spawn functions share while a world is loaded, like [LinkContext] for the linkers.
+struct SpawnContext<'a> {
+    bodies: &'a mut RigidBodySet,
+    colliders: &'a mut ColliderSet,
+    resources: &'a HashMap<String, WorldResource>,
+
+    /// Spawned entities by their name in the scene, without the prefix of the options.
+    entities: &'a mut HashMap<String, SpawnedWorldEntity>,
+
+    /// Entities that were skipped because of missing or invalid resources.
+    errors: &'a mut Vec<LoaderError>,
+    options: &'a RapierLoadOptions,
 }
 
+/// `compound` is the collider of the parent body that this collision shape was merged into.
+/// Entities with invalid resources are logged and added to the `errors` of the context, without
+/// their data, but their children are still spawned.
 fn spawn_entity(
     entity: &WorldEntity,
-    parent_transform: Matrix4<f32>,
+    parent_transform: Matrix4<Real>,
+    parent: Option<&str>,
     parent_data: Option<&SpawnedWorldEntityData>,
+    compound: Option<ColliderHandle>,
 
-    bodies: &mut RigidBodySet,
-    colliders: &mut ColliderSet,
     islands: &mut IslandManager,
-    resources: &HashMap<String, WorldResource>,
-    entities: &mut HashMap<String, SpawnedWorldEntity>,
-) -> Option<SpawnedWorldEntityData> {
-    let relative_transform = get_or_return_val!(get_entity_transform(entity), None);
+    context: &mut SpawnContext<'_>,
+) -> Result<Option<SpawnedWorldEntityData>, TransformError> {
+    let options = context.options;
+    if is_left_out(entity) {
+        return Ok(None);
+    }
+
+    // Entities without a transform are placed at their parent, so that their children still
+    // get spawned
+    let relative_transform = get_entity_transform(entity).map_err(|err| {
+        error!("{}", err);
+        err
+    })?;
     let absolute_transform = parent_transform * relative_transform;
     let node_transform = NodeTransform::from_matrix(&absolute_transform);
 
-    let data = spawn_entity_data(
-        entity,
-        parent_data,
-        node_transform,
-        &relative_transform,
-        bodies,
-        colliders,
-        resources,
-        entities,
-    );
+    // Rapier drops the scene scale from the transforms, so offsets from the parent are scaled
+    let relative_transform = scale_translation(&relative_transform, options.scene_scale);
+
+    // Filtered out entities are only placed for the sake of their descendants. Shapes merged
+    // into the collider of their body are part of it already, so they are always kept
+    let included = match &options.filter {
+        Some(filter) if compound.is_none() && !filter.includes(entity, &absolute_transform) => {
+            if !filter.includes_descendants(entity, &absolute_transform) {
+                return Ok(None);
+            }
+            false
+        }
+        _ => true,
+    };
+
+    let spawned = if included {
+        spawn_entity_data(
+            entity,
+            parent,
+            parent_data,
+            compound,
+            node_transform,
+            &relative_transform,
+            context,
+        )
+    } else {
+        // Placeholder without anything in rapier, so that the descendants still have a parent
+        context.entities.insert(
+            entity.name.clone(),
+            SpawnedWorldEntity {
+                entity_type: entity.entity_type.clone(),
+                parent: parent.map(|x| x.to_owned()),
+                local_transform: NodeTransform::from_matrix(&relative_transform),
+                world_transform: node_transform,
+                metadata: entity.metadata.clone(),
+                data: SpawnedWorldEntityData::Node,
+                linear_velocity: None,
+            },
+        );
+        Ok(None)
+    };
+    let data = match spawned {
+        Ok(data) => data,
+        Err(err) => {
+            warn!("skipping {}: {}", entity.name, err);
+            context.errors.push(err);
+            None
+        }
+    };
+
+    if let (Some(data), Some(hook)) = (&data, &options.on_spawn) {
+        let spawned = SpawnRef {
+            data,
+            bodies: context.bodies,
+            colliders: context.colliders,
+        };
+        hook.call(entity, spawned);
+    }
+
+    // Bodies may ask for their shapes to be merged into a single collider
+    let mut merged: Option<(ColliderHandle, Vec<&str>)> = None;
+    if let Some(SpawnedWorldEntityData::PhysicsBody((handle, _))) = &data {
+        let compound = entity.metadata.get("compound").and_then(|x| x.as_bool()) == Some(true);
+        if compound && !options.free_colliders {
+            merged = spawn_compound_collider(entity, *handle, context);
+        }
+    }
 
     if let Some(children) = &entity.children {
         for child in children {
+            let compound = merged
+                .as_ref()
+                .filter(|(_, names)| names.contains(&child.name.as_str()))
+                .map(|(handle, _)| *handle);
+
             spawn_entity(
                 child,
                 absolute_transform,
+                Some(&entity.name),
                 data.as_ref(),
-                bodies,
-                colliders,
+                compound,
                 islands,
-                resources,
-                entities,
-            );
+                context,
+            )?;
         }
     }
 
-    return data;
+    return Ok(data);
 }
 
 pub fn spawn_body(
     body_type: RigidBodyType,
     transform: &NodeTransform,
+    entity: &WorldEntity,
     bodies: &mut RigidBodySet,
 ) -> RigidBodyHandle {
-    let rb = RigidBodyBuilder::new(body_type)
-        .position(transform.into())
-        .build();
+    let mut builder = RigidBodyBuilder::new(body_type).position(transform.into());
+
+    if entity.metadata.get("ccd").and_then(|x| x.as_bool()) == Some(true) {
+        if body_type == RigidBodyType::Fixed {
+            debug!(
+                "ignoring ccd on static body {}, it never moves",
+                entity.name
+            );
+        } else {
+            builder = builder.ccd_enabled(true);
+        }
+    }
+
+    // Only override the rapier defaults with properties that were exported
+    if let EntityData::RigidBody3D(body) = &entity.data {
+        if let Some(gravity_scale) = body.gravity_scale {
+            builder = builder.gravity_scale(to_real(gravity_scale));
+        }
+        if let Some(linear_damp) = body.linear_damp {
+            builder = builder.linear_damping(to_real(linear_damp));
+        }
+        if let Some(angular_damp) = body.angular_damp {
+            builder = builder.angular_damping(to_real(angular_damp));
+        }
+        if let Some(axis_lock) = body.axis_lock {
+            builder = builder.locked_axes(LockedAxes::from_bits_truncate(axis_lock));
+        }
+        if let Some(can_sleep) = body.can_sleep {
+            builder = builder.can_sleep(can_sleep);
+        }
+        match body.activation() {
+            BodyActivation::Awake => {}
+            BodyActivation::Asleep => builder = builder.sleeping(true),
+            BodyActivation::Disabled => builder = builder.enabled(false),
+        }
+    }
+
+    match MassOverride::from_metadata(&entity.metadata) {
+        Ok(Some(mass)) if body_type == RigidBodyType::Dynamic => {
+            builder = builder.additional_mass_properties(MassProperties::new(
+                to_vector(&mass.center_of_mass).into(),
+                to_real(mass.mass),
+                to_vector(&mass.principal_inertia),
+            ));
+        }
+        Ok(Some(_)) => debug!(
+            "ignoring mass properties of {}, it isn't a dynamic body",
+            entity.name
+        ),
+        Ok(None) => {}
+        Err(reason) => warn!(
+            "ignoring mass properties of {}, using the mass of its colliders: {}",
+            entity.name, reason
+        ),
+    }
 
-    return bodies.insert(rb);
+    return bodies.insert(builder.build());
+}
+
+/// Hidden entities can be left out of the simulation along with their children.
+fn is_left_out(entity: &WorldEntity) -> bool {
+    let hidden = entity.data.visible() == Some(false);
+    hidden && entity.metadata.get("disabled").and_then(|x| x.as_bool()) == Some(true)
+}
+
+/// Merges the convex collision shapes directly under a body into a single compound collider.
+/// Returns the collider along with the names of the merged shapes, which all share it.
+/// Other shapes, and the metadata of the merged ones (e.g. `sensor`), are left to be spawned
+/// as separate colliders. The compound collider takes its [ColliderMaterial] from the body.
+fn spawn_compound_collider<'a>(
+    entity: &'a WorldEntity,
+    body: RigidBodyHandle,
+    context: &mut SpawnContext<'_>,
+) -> Option<(ColliderHandle, Vec<&'a str>)> {
+    let options = context.options;
+    let mut shapes = vec![];
+    let mut names = vec![];
+
+    for child in entity.children.iter().flatten().filter(|x| !is_left_out(x)) {
+        // Invalid shapes are reported when they are spawned on their own
+        let (shape, resource) = match plan_entity(child, false, context.resources) {
+            Ok(SpawnPlan::Collider {
+                shape, resource, ..
+            }) if !shape.is_disabled() => (shape, resource),
+            _ => continue,
+        };
+
+        let collider = get_or_continue!(parse_collider(
+            &shape.shape,
+            resource,
+            None,
+            false,
+            &child.metadata,
+            options.collider_margin,
+            options.scene_scale,
+        )
+        .ok());
+        if !collider.shape().is_convex() {
+            continue;
+        }
+
+        // Malformed transforms are reported when the shape is spawned on its own
+        let relative_transform = get_or_continue!(get_entity_transform(child).ok());
+        let relative_transform = scale_translation(&relative_transform, options.scene_scale);
+        let pos = Isometry3::from(NodeTransform::from_matrix(&relative_transform));
+
+        shapes.push((pos * collider.position(), collider.shared_shape().clone()));
+        names.push(child.name.as_str());
+    }
+
+    if shapes.is_empty() {
+        return None;
+    }
+
+    let material = ColliderMaterial::from_metadata(&entity.metadata);
+    let collider = with_material(ColliderBuilder::compound(shapes), &material).build();
+    let handle = context
+        .colliders
+        .insert_with_parent(collider, body, context.bodies);
+
+    return Some((handle, names));
 }
 
 fn spawn_collision_shape(
     entity: &WorldEntity,
     shape: &CollisionShapeData,
+    resource: &ResourceData,
+    sensor: bool,
     absolute_transform: &NodeTransform,
-    relative_transform: &Matrix4<f32>,
-    colliders: &mut ColliderSet,
-    bodies: &mut RigidBodySet,
+    relative_transform: &Matrix4<Real>,
 
     parent_data: Option<&SpawnedWorldEntityData>,
-    resources: &HashMap<String, WorldResource>,
-) -> Option<SpawnedWorldEntityData> {
-    if let Some(parent_data) = parent_data {
-        if let SpawnedWorldEntityData::PhysicsBody((parent_handle, parent_body_type)) = parent_data
-        {
-            let mut collider: Collider = if let Some(col) =
-                parse_collider(resources, shape, Some(parent_body_type), &entity.metadata)
-            {
-                col
-            } else {
-                return None;
-            };
+    context: &mut SpawnContext<'_>,
+) -> Result<Option<SpawnedWorldEntityData>, LoaderError> {
+    if shape.is_disabled() {
+        return Ok(None);
+    }
 
-            // Use transform relative to the parent body
-            let pos = NodeTransform::from_matrix(relative_transform);
-            collider.set_position(pos.into());
+    let options = context.options;
+    let parent_body = parent_body(parent_data, options);
+    let collider = parse_collider(
+        &shape.shape,
+        resource,
+        parent_body.map(|(_, body_type)| body_type),
+        sensor,
+        &entity.metadata,
+        options.collider_margin,
+        options.scene_scale,
+    )?;
+
+    let handle = insert_collider(
+        collider,
+        parent_body.map(|(handle, _)| handle),
+        absolute_transform,
+        relative_transform,
+        context.colliders,
+        context.bodies,
+    );
+    return Ok(Some(SpawnedWorldEntityData::Collider(handle)));
+}
+
+/// Spawns the trimesh collider of a mesh instance, whose mesh is read with the
+/// [MeshLoader](mesh::MeshLoader) of the options.
+fn spawn_mesh_collider(
+    instance: &MeshInstanceData,
+    path: &str,
+    absolute_transform: &NodeTransform,
+    relative_transform: &Matrix4<Real>,
+
+    parent_data: Option<&SpawnedWorldEntityData>,
+    context: &mut SpawnContext<'_>,
+) -> Result<Option<SpawnedWorldEntityData>, LoaderError> {
+    let collider = load_mesh_collider(&instance.mesh, path, context.options)?;
+    let handle = insert_collider(
+        collider,
+        parent_body(parent_data, context.options).map(|(handle, _)| handle),
+        absolute_transform,
+        relative_transform,
+        context.colliders,
+        context.bodies,
+    );
+    return Ok(Some(SpawnedWorldEntityData::Collider(handle)));
+}
+
+/// Reads the `ArrayMesh` resource `name` from `path` into a trimesh collider.
+fn load_mesh_collider(
+    name: &str,
+    path: &str,
+    options: &RapierLoadOptions,
+) -> Result<Collider, LoaderError> {
+    let mesh = options
+        .mesh_loader
+        .load(path)
+        .ok_or_else(|| LoaderError::InvalidResource {
+            resource: name.to_owned(),
+            reason: format!("could not be read from {}", path),
+        })?;
+
+    let scale = options.scene_scale;
+    let vertices = mesh.vertices.into_iter().map(|x| x * scale).collect();
+    return Ok(ColliderBuilder::trimesh(vertices, mesh.indices).build());
+}
+
+/// Spawns a collider for every cell of a grid map whose item is a collision shape, or a mesh
+/// that the [MeshLoader](mesh::MeshLoader) of the options can read. Other items are skipped.
+fn spawn_grid_map_colliders(
+    entity: &WorldEntity,
+    grid: &GridMapData,
+    absolute_transform: &NodeTransform,
+    relative_transform: &Matrix4<Real>,
+
+    parent_data: Option<&SpawnedWorldEntityData>,
+    context: &mut SpawnContext<'_>,
+) -> Result<Option<SpawnedWorldEntityData>, LoaderError> {
+    let options = context.options;
+    let parent_body = parent_body(parent_data, options);
 
-            let handle = colliders.insert_with_parent(collider, parent_handle.clone(), bodies);
-            return Some(SpawnedWorldEntityData::Collider(handle));
+    // Items are placed many times, so their colliders are only created once. They are all
+    // created before any is inserted, so that an invalid item doesn't leave colliders behind
+    let mut items: HashMap<&str, Option<Collider>> = HashMap::new();
+    for (_, name) in grid.items() {
+        if items.contains_key(name) {
+            continue;
         }
-    }
 
-    let mut collider: Collider =
-        if let Some(col) = parse_collider(resources, shape, None, &entity.metadata) {
-            col
-        } else {
-            return None;
+        let resource = &context.resources[name].data;
+        let collider = match resource {
+            ResourceData::ArrayMesh(mesh) if options.mesh_loader.is_set() => {
+                Some(load_mesh_collider(name, &mesh.path, options)?)
+            }
+            _ if resource.is_collision_shape() => Some(parse_collider(
+                name,
+                resource,
+                parent_body.map(|(_, body_type)| body_type),
+                false,
+                &entity.metadata,
+                options.collider_margin,
+                options.scene_scale,
+            )?),
+            _ => None,
         };
+        items.insert(name, collider);
+    }
 
-    collider.set_position(absolute_transform.into());
-    let handle = colliders.insert(collider);
+    let mut handles = vec![];
+    for (cell, name) in grid.items() {
+        let collider = get_or_continue!(&items[name]).clone();
+        let cell = grid.cell_transform(cell).map(to_real);
+        let cell = Matrix4::from_column_slice(&cell);
+        let absolute = NodeTransform::from_matrix(&(absolute_transform.matrix * cell));
+
+        // The absolute transform is scaled already, unlike the offset from the parent body
+        let cell = scale_translation(&cell, options.scene_scale);
+
+        handles.push(insert_collider(
+            collider,
+            parent_body.map(|(handle, _)| handle),
+            &absolute,
+            &(relative_transform * cell),
+            context.colliders,
+            context.bodies,
+        ));
+    }
 
-    return Some(SpawnedWorldEntityData::Collider(handle));
+    return Ok(Some(SpawnedWorldEntityData::GridMap(handles)));
 }
 
+/// Body that the colliders of an entity get attached to, unless they are spawned free.
+fn parent_body<'a>(
+    parent_data: Option<&'a SpawnedWorldEntityData>,
+    options: &RapierLoadOptions,
+) -> Option<(RigidBodyHandle, &'a RigidBodyType)> {
+    match parent_data {
+        Some(SpawnedWorldEntityData::PhysicsBody((handle, body_type)))
+            if !options.free_colliders =>
+        {
+            Some((*handle, body_type))
+        }
+        _ => None,
+    }
+}
+
+/// Inserts a collider attached to the `parent` body, or placed in world space without one.
+fn insert_collider(
+    mut collider: Collider,
+    parent: Option<RigidBodyHandle>,
+    absolute_transform: &NodeTransform,
+    relative_transform: &Matrix4<Real>,
+    colliders: &mut ColliderSet,
+    bodies: &mut RigidBodySet,
+) -> ColliderHandle {
+    match parent {
+        Some(parent) => {
+            // Use transform relative to the parent body, keeping the local offset of the shape
+            let pos = NodeTransform::from_matrix(relative_transform);
+            collider.set_position(Isometry3::from(pos) * collider.position());
+            colliders.insert_with_parent(collider, parent, bodies)
+        }
+        None => {
+            collider.set_position(Isometry3::from(absolute_transform) * collider.position());
+            colliders.insert(collider)
+        }
+    }
+}
+
+/// Creates the collider of the shape resource `shape_name`, which was resolved by [plan_entity].
 fn parse_collider(
-    resources: &HashMap<String, WorldResource>,
-    shape: &CollisionShapeData,
-    _parent_body_type: Option<&RigidBodyType>,
-    metadata: &HashMap<String, Value>,
-) -> Option<Collider> {
-    let res = get_or_return_val!(resources.get(&shape.shape), None);
-
-    let mut collider_builder = match &res.data {
-        ResourceData::BoxCollisionShape(shape) => ColliderBuilder::cuboid(
-            shape.size[0] / 2.0,
-            shape.size[1] / 2.0,
-            shape.size[2] / 2.0,
-        ),
-        ResourceData::SphereCollisionShape(shape) => ColliderBuilder::ball(shape.radius),
+    shape_name: &str,
+    resource: &ResourceData,
+    parent_body_type: Option<&RigidBodyType>,
+    sensor: bool,
+    metadata: &Metadata,
+    margin: Real,
+    scale: Real,
+) -> Result<Collider, LoaderError> {
+    let margin = metadata
+        .get("collider_margin")
+        .and_then(|x| x.as_f64())
+        .map(|x| x as Real)
+        .unwrap_or(margin);
+
+    let mut collider_builder = match resource {
+        ResourceData::BoxCollisionShape(shape) if margin > 0.0 => {
+            // Round the edges inwards, so that the box keeps the size it has in Godot
+            let half = |size: Real| (size / 2.0 - margin).max(0.0);
+            let size = to_vector(&shape.size) * scale;
+            ColliderBuilder::round_cuboid(half(size.x), half(size.y), half(size.z), margin)
+        }
+        ResourceData::BoxCollisionShape(shape) => {
+            let half = to_vector(&shape.size) * scale / 2.0;
+            ColliderBuilder::cuboid(half.x, half.y, half.z)
+        }
+        ResourceData::SphereCollisionShape(shape) => {
+            ColliderBuilder::ball(to_real(shape.radius) * scale)
+        }
         ResourceData::ConcavePolygonCollisionShape(shape) => {
-            let mut verts = vec![];
-            for i in (0..shape.data.len()).step_by(3) {
-                verts.push(Point3::new(
-                    shape.data[i],
-                    shape.data[i + 1],
-                    shape.data[i + 2],
-                ));
+            let mut verts: Vec<Point3<Real>> = vec![];
+            for v in shape.data.chunks_exact(3) {
+                let vertex = Vector3::new(to_real(v[0]), to_real(v[1]), to_real(v[2]));
+                verts.push(Point3::from(vertex * scale));
             }
-            ColliderBuilder::polyline(verts, None)
+
+            // Dynamic bodies can't collide properly with concave shapes, so split them into
+            // convex parts. This runs VHACD, which is slow, so it's opt-in.
+            let dynamic = parent_body_type == Some(&RigidBodyType::Dynamic);
+            if cfg!(feature = "convex_decomposition") && dynamic {
+                let indices: Vec<[u32; 3]> = (0..verts.len() as u32 / 3)
+                    .map(|i| [i * 3, i * 3 + 1, i * 3 + 2])
+                    .collect();
+                ColliderBuilder::convex_decomposition(&verts, &indices)
+            } else {
+                ColliderBuilder::polyline(verts, None)
+            }
+        }
+        ResourceData::HeightMapCollisionShape(shape) => {
+            // Rows run along the Z axis, columns along X, with one unit between samples
+            let heights = DMatrix::from_fn(shape.depth, shape.width, |row, col| {
+                to_real(shape.data[row * shape.width + col])
+            });
+            let size = Vector3::new((shape.width - 1) as Real, 1.0, (shape.depth - 1) as Real);
+            ColliderBuilder::heightfield(heights, size * scale)
+        }
+        ResourceData::WorldBoundaryCollisionShape(shape) => {
+            let normal = to_vector(&shape.normal);
+            let origin = to_vector(&shape.origin()) * scale;
+
+            // The half-space passes through its local origin, so offset it onto the plane
+            ColliderBuilder::halfspace(Unit::new_normalize(normal)).translation(origin)
         }
         _ => {
-            panic!("invalid shape");
+            return Err(LoaderError::InvalidResource {
+                resource: shape_name.to_owned(),
+                reason: "is not a collision shape".to_owned(),
+            });
         }
     };
 
-    if let Some(sensor_value) = metadata.get("sensor") {
-        if let Some(sensor) = sensor_value.as_bool() {
-            collider_builder = collider_builder
-                .sensor(sensor)
-                .active_collision_types(ActiveCollisionTypes::all())
-                .active_events(ActiveEvents::all());
-        }
+    // Shapes of an area are always sensors, which the plan already accounts for
+    let sensor = if sensor {
+        Some(true)
+    } else {
+        metadata.get("sensor").and_then(|x| x.as_bool())
+    };
+
+    if let Some(sensor) = sensor {
+        collider_builder = collider_builder
+            .sensor(sensor)
+            .active_collision_types(ActiveCollisionTypes::all())
+            .active_events(ActiveEvents::all());
     }
 
-    return Some(collider_builder.build());
+    let material = ColliderMaterial::from_metadata(metadata);
+    return Ok(with_material(collider_builder, &material).build());
+}
+
+/// Overrides the rapier defaults with the properties of `material` that were set.
+fn with_material(mut builder: ColliderBuilder, material: &ColliderMaterial) -> ColliderBuilder {
+    if let Some(friction) = material.friction {
+        builder = builder.friction(to_real(friction));
+    }
+    if let Some(restitution) = material.restitution {
+        builder = builder.restitution(to_real(restitution));
+    }
+    if let Some(density) = material.density {
+        builder = builder.density(to_real(density));
+    }
+
+    builder
+}
+
+/// Copy of a transform whose translation is multiplied by `scale`.
+fn scale_translation(transform: &Matrix4<Real>, scale: Real) -> Matrix4<Real> {
+    let mut transform = *transform;
+    transform.fixed_view_mut::<3, 1>(0, 3).scale_mut(scale);
+    transform
+}
+
+/// Widens a serialized `f32` to rapier's float type, which is only a conversion with the
+/// `rapier3d-f64` feature.
+#[allow(clippy::useless_conversion)]
+pub(crate) fn to_real(x: f32) -> Real {
+    x.into()
+}
+
+/// Widens a serialized `f32` vector to rapier's float type.
+fn to_vector(&[x, y, z]: &[f32; 3]) -> Vector3<Real> {
+    Vector3::new(to_real(x), to_real(y), to_real(z))
+}
+
+/// Body type requested with the `rigid_body_type` metadata value, overriding the Godot type.
+/// `kinematic` is the kinematic type that the loader picks for the entity itself.
+fn body_type_override(entity: &WorldEntity, kinematic: RigidBodyType) -> Option<RigidBodyType> {
+    let value = entity.metadata.get("rigid_body_type")?.as_str()?;
+    match value {
+        "static" => Some(RigidBodyType::Fixed),
+        "dynamic" => Some(RigidBodyType::Dynamic),
+        "kinematic" => Some(kinematic),
+        _ => {
+            warn!(
+                "ignoring unknown rigid_body_type {} of {}, expected static, dynamic or kinematic",
+                value, entity.name
+            );
+            None
+        }
+    }
 }
 
 fn spawn_entity_data(
     entity: &WorldEntity,
+    parent: Option<&str>,
     parent_data: Option<&SpawnedWorldEntityData>,
+    compound: Option<ColliderHandle>,
     absolute_transform: NodeTransform,
-    relative_transform: &Matrix4<f32>,
-    bodies: &mut RigidBodySet,
-    colliders: &mut ColliderSet,
-    resources: &HashMap<String, WorldResource>,
-    entities: &mut HashMap<String, SpawnedWorldEntity>,
-) -> Option<SpawnedWorldEntityData> {
-    let body_type = match &entity.data {
-        EntityData::StaticBody3D(_) => Some(RigidBodyType::Fixed),
-        EntityData::KinematicBody3D(_) => Some(RigidBodyType::KinematicVelocityBased),
-        EntityData::RigidBody3D(_) => Some(RigidBodyType::Dynamic),
-        _ => None,
+    relative_transform: &Matrix4<Real>,
+    context: &mut SpawnContext<'_>,
+) -> Result<Option<SpawnedWorldEntityData>, LoaderError> {
+    let options = context.options;
+    let in_area = matches!(parent_data, Some(SpawnedWorldEntityData::Area));
+    let plan = plan_entity(entity, in_area, context.resources)?;
+
+    // Moved by step_kinematic_bodies, which only sets the next position of these
+    let kinematic = match plan {
+        SpawnPlan::KinematicBody(body) if body.linear_velocity.is_some() => {
+            RigidBodyType::KinematicPositionBased
+        }
+        _ => RigidBodyType::KinematicVelocityBased,
     };
+    let body_type = match plan {
+        SpawnPlan::StaticBody => Some(RigidBodyType::Fixed),
+        SpawnPlan::KinematicBody(_) => Some(kinematic),
+        SpawnPlan::RigidBody(_) => Some(RigidBodyType::Dynamic),
+        _ => None,
+    }
+    .map(|body_type| body_type_override(entity, kinematic).unwrap_or(body_type));
 
     let data = if let Some(body_type) = body_type {
-        let handle = spawn_body(body_type, &absolute_transform, bodies);
+        let handle = spawn_body(body_type, &absolute_transform, entity, context.bodies);
         Some(SpawnedWorldEntityData::PhysicsBody((handle, body_type)))
+    } else if let Some(handle) = compound {
+        // Already part of the compound collider of the parent body
+        Some(SpawnedWorldEntityData::Collider(handle))
     } else {
-        match &entity.data {
-            EntityData::CollisionShape3D(shape) => spawn_collision_shape(
+        match plan {
+            SpawnPlan::Area => Some(SpawnedWorldEntityData::Area),
+            SpawnPlan::Collider {
+                shape,
+                resource,
+                sensor,
+            } => spawn_collision_shape(
                 entity,
                 shape,
+                resource,
+                sensor,
                 &absolute_transform,
                 relative_transform,
-                colliders,
-                bodies,
                 parent_data,
-                resources,
-            ),
-            _ => None,
+                context,
+            )?,
+            SpawnPlan::GridMap(grid) => spawn_grid_map_colliders(
+                entity,
+                grid,
+                &absolute_transform,
+                relative_transform,
+                parent_data,
+                context,
+            )?,
+            SpawnPlan::Mesh {
+                instance,
+                resource: ResourceData::ArrayMesh(mesh),
+            } if options.mesh_loader.is_set() => spawn_mesh_collider(
+                instance,
+                &mesh.path,
+                &absolute_transform,
+                relative_transform,
+                parent_data,
+                context,
+            )?,
+            _ => Some(SpawnedWorldEntityData::Node),
         }
     };
 
+    let linear_velocity = match &entity.data {
+        EntityData::KinematicBody3D(body) => body
+            .linear_velocity
+            .as_ref()
+            .map(|x| to_vector(x) * options.scene_scale),
+        _ => None,
+    };
+
     if let Some(data) = &data {
-        entities.insert(
+        debug!("spawned {} as {:?}", entity.name, data);
+        context.entities.insert(
             entity.name.clone(),
             SpawnedWorldEntity {
                 entity_type: entity.entity_type.clone(),
-                transform: absolute_transform.clone(),
+                parent: parent.map(|x| x.to_owned()),
+                local_transform: NodeTransform::from_matrix(relative_transform),
+                world_transform: absolute_transform.clone(),
                 metadata: entity.metadata.clone(),
                 data: data.clone(),
+                linear_velocity,
             },
         );
     }
 
-    return data;
+    return Ok(data);
 }
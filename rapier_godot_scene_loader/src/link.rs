@@ -0,0 +1,52 @@
+use std::{collections::HashMap, sync::Arc};
+
+use common::{error::LoaderError, WorldEntity};
+use rapier3d::{
+    dynamics::{ImpulseJointSet, RigidBodySet},
+    geometry::ColliderSet,
+};
+
+use crate::SpawnedWorldEntity;
+
+type Link = dyn Fn(&WorldEntity, &mut LinkContext<'_>) -> Result<(), LoaderError> + Send + Sync;
+
+/// Resolves references between entities by name, e.g. the bodies of a joint or the target of
+/// a follower. Linkers run once all entities are spawned, so that references can point anywhere
+/// in the tree. Every linker is called with every entity, in the order the linkers were added.
+#[derive(Clone)]
+pub struct Linker(Arc<Link>);
+
+impl Linker {
+    pub fn new(
+        link: impl Fn(&WorldEntity, &mut LinkContext<'_>) -> Result<(), LoaderError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self(Arc::new(link))
+    }
+
+    pub fn link(
+        &self,
+        entity: &WorldEntity,
+        context: &mut LinkContext<'_>,
+    ) -> Result<(), LoaderError> {
+        (self.0)(entity, context)
+    }
+}
+
+impl std::fmt::Debug for Linker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Linker")
+    }
+}
+
+/// What the linkers can look up and modify.
+pub struct LinkContext<'a> {
+    /// All spawned entities by their name in the scene, without the prefix of the options.
+    pub entities: &'a mut HashMap<String, SpawnedWorldEntity>,
+
+    pub bodies: &'a mut RigidBodySet,
+    pub colliders: &'a mut ColliderSet,
+    pub impulse_joints: &'a mut ImpulseJointSet,
+}
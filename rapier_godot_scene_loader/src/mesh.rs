@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use rapier3d::{math::Real, na::Point3};
+
+/// Vertices and triangles of a mesh, used to build a trimesh collider.
+#[derive(Clone, Debug, Default)]
+pub struct TriMeshData {
+    pub vertices: Vec<Point3<Real>>,
+    pub indices: Vec<[u32; 3]>,
+}
+
+/// Reads the geometry of the `ArrayMesh` resources of a scene, which only reference mesh files
+/// by their `res://` path. The loader receives that path, including any `#` label, and returns
+/// `None` if it can't provide the mesh. By default no loader is set, so no mesh colliders
+/// are created.
+#[derive(Clone, Default)]
+pub struct MeshLoader(Option<Arc<dyn Fn(&str) -> Option<TriMeshData> + Send + Sync>>);
+
+impl MeshLoader {
+    pub fn new(load: impl Fn(&str) -> Option<TriMeshData> + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(load)))
+    }
+
+    /// Reads glTF files from the filesystem, resolving `res://` paths against `root`, which is
+    /// usually the directory of the Godot project. A `#MeshN/PrimitiveM` label like Bevy's selects
+    /// a single primitive, otherwise the primitives of all meshes in the file are merged.
+    #[cfg(feature = "gltf")]
+    pub fn gltf(root: impl Into<std::path::PathBuf>) -> Self {
+        let root = root.into();
+        Self::new(move |path| load_gltf_mesh(&root, path))
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.0.is_some()
+    }
+
+    pub fn load(&self, res_path: &str) -> Option<TriMeshData> {
+        self.0.as_ref().and_then(|load| load(res_path))
+    }
+}
+
+impl std::fmt::Debug for MeshLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(_) => f.write_str("MeshLoader(custom)"),
+            None => f.write_str("MeshLoader(none)"),
+        }
+    }
+}
+
+#[cfg(feature = "gltf")]
+fn load_gltf_mesh(root: &std::path::Path, res_path: &str) -> Option<TriMeshData> {
+    let (path, label) = match res_path.split_once('#') {
+        Some((path, label)) => (path, Some(label)),
+        None => (res_path, None),
+    };
+
+    // Parses e.g. `Mesh0/Primitive1` into the mesh and primitive indices
+    let selected = match label {
+        Some(label) => {
+            let (mesh, primitive) = label.split_once('/')?;
+            let mesh: usize = mesh.strip_prefix("Mesh")?.parse().ok()?;
+            let primitive: usize = primitive.strip_prefix("Primitive")?.parse().ok()?;
+            Some((mesh, primitive))
+        }
+        None => None,
+    };
+
+    let file = root.join(path.trim_start_matches("res://"));
+    let (document, buffers, _) = match gltf::import(&file) {
+        Ok(imported) => imported,
+        Err(err) => {
+            log::warn!("unable to read mesh {}: {}", file.display(), err);
+            return None;
+        }
+    };
+
+    let mut data = TriMeshData::default();
+    for mesh in document.meshes() {
+        for primitive in mesh.primitives() {
+            if selected.is_some_and(|selected| selected != (mesh.index(), primitive.index())) {
+                continue;
+            }
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            let offset = data.vertices.len() as u32;
+            let positions: Vec<Point3<Real>> = match reader.read_positions() {
+                Some(positions) => positions
+                    .map(|[x, y, z]| {
+                        Point3::new(crate::to_real(x), crate::to_real(y), crate::to_real(z))
+                    })
+                    .collect(),
+                None => continue,
+            };
+
+            // Primitives without indices list their triangles vertex by vertex
+            let indices: Vec<u32> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..positions.len() as u32).collect(),
+            };
+
+            data.vertices.extend(positions);
+            data.indices.extend(
+                indices
+                    .chunks_exact(3)
+                    .map(|x| [offset + x[0], offset + x[1], offset + x[2]]),
+            );
+        }
+    }
+
+    if data.indices.is_empty() {
+        return None;
+    }
+
+    Some(data)
+}
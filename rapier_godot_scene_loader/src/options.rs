@@ -0,0 +1,68 @@
+use common::transform::CoordinateSystem;
+use rapier3d::{math::Real, na::Matrix4};
+
+use crate::{filter::SpawnFilter, hook::SpawnHook, link::Linker, mesh::MeshLoader};
+
+/// Options controlling how a scene is loaded into rapier.
+#[derive(Clone, Debug)]
+pub struct RapierLoadOptions {
+    /// Transform applied to the whole scene, after the coordinate system conversion.
+    pub transform: Option<Matrix4<Real>>,
+
+    /// Up-axis convention of the scene, converted to rapier's Y-up.
+    pub coordinate_system: CoordinateSystem,
+
+    /// Uniform scale of the whole scene, e.g. 0.5 for a miniature. Positions are scaled along
+    /// with the collision shapes and trimesh colliders, as rapier can't scale colliders.
+    /// Non-uniform scaling is out of scope, as is scale in the `transform` of the options,
+    /// which is dropped like the scale of the nodes of the scene. Defaults to 1.0.
+    pub scene_scale: Real,
+
+    /// Prepended to the names of the returned entities.
+    pub prefix: String,
+
+    /// Rounding border of the collision shapes, overridden per shape with the `collider_margin`
+    /// metadata value. Only box shapes support it, and keep their size by rounding inwards.
+    /// Defaults to 0.0, which leaves the shapes untouched.
+    pub collider_margin: Real,
+
+    /// Spawns all collision shapes as standalone colliders placed in world space, even those
+    /// of physics bodies, e.g. for static level geometry. Bodies are still spawned, but without
+    /// colliders, and `compound` metadata is ignored. Sensors stay sensors, and like every
+    /// sensor they report intersections with fixed colliders, so also with other free ones.
+    pub free_colliders: bool,
+
+    /// Reads the `ArrayMesh` resources of mesh instances, which then also get a fixed trimesh
+    /// collider, or one attached to their parent body. Meshes are read from the files
+    /// that the resources reference, e.g. with [MeshLoader::gltf].
+    /// By default no loader is set, and mesh instances don't collide.
+    pub mesh_loader: MeshLoader,
+
+    /// Resolve references between entities once the whole scene is spawned. Joints are always
+    /// connected first, by a built-in linker.
+    pub linkers: Vec<Linker>,
+
+    /// Called for every spawned entity right after its body or colliders are created,
+    /// before its children.
+    pub on_spawn: Option<SpawnHook>,
+
+    /// Only spawns the entities that pass the filter, by default all entities are spawned.
+    pub filter: Option<SpawnFilter>,
+}
+
+impl Default for RapierLoadOptions {
+    fn default() -> Self {
+        Self {
+            transform: None,
+            coordinate_system: CoordinateSystem::default(),
+            scene_scale: 1.0,
+            prefix: String::new(),
+            collider_margin: 0.0,
+            free_colliders: false,
+            mesh_loader: MeshLoader::default(),
+            linkers: vec![],
+            on_spawn: None,
+            filter: None,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use rapier3d::{
+    dynamics::{
+        CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
+        RigidBodySet,
+    },
+    geometry::{BroadPhase, ColliderSet, NarrowPhase},
+    math::Real,
+    na::Vector3,
+    pipeline::PhysicsPipeline,
+};
+
+/// Steps a loaded world `steps` times with rapier's default time step of 1/60 s, e.g. to
+/// settle a scene or to run it headless in tests and on servers.
+///
+/// The pipeline and the contacts are created for every call and dropped afterwards, so this
+/// fits one-off runs. Games that step every frame should keep their own [PhysicsPipeline].
+pub fn step_world(
+    bodies: &mut RigidBodySet,
+    colliders: &mut ColliderSet,
+    islands: &mut IslandManager,
+    impulse_joints: &mut ImpulseJointSet,
+    gravity: Vector3<Real>,
+    steps: usize,
+) {
+    let mut pipeline = PhysicsPipeline::new();
+    let integration_parameters = IntegrationParameters::default();
+    let mut broad_phase = BroadPhase::new();
+    let mut narrow_phase = NarrowPhase::new();
+    let mut multibody_joints = MultibodyJointSet::new();
+    let mut ccd_solver = CCDSolver::new();
+
+    for _ in 0..steps {
+        pipeline.step(
+            &gravity,
+            &integration_parameters,
+            islands,
+            &mut broad_phase,
+            &mut narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            &mut multibody_joints,
+            &mut ccd_solver,
+            None,
+            &(),
+            &(),
+        );
+    }
+}
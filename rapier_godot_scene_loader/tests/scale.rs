@@ -0,0 +1,51 @@
+#[cfg(feature = "rapier3d-f64")]
+extern crate rapier3d_f64 as rapier3d;
+
+use common::builder::{translation, SceneWorldBuilder};
+use rapier3d::{
+    dynamics::{ImpulseJointSet, IslandManager, RigidBodySet},
+    geometry::ColliderSet,
+    math::Real,
+};
+use rapier_godot_scene_loader::{
+    load_world_to_rapier_with, RapierLoadOptions, SpawnedWorldEntityData,
+};
+
+const EPSILON: Real = 1.0e-4;
+
+#[test]
+fn scene_scale_scales_positions_and_shapes() {
+    let world = SceneWorldBuilder::new()
+        .add_rigid_body("Ball", translation([1.0, 4.0, 0.0]))
+        .with_sphere_collider(0.5)
+        .build();
+    let options = RapierLoadOptions {
+        scene_scale: 2.0,
+        ..RapierLoadOptions::default()
+    };
+
+    let mut bodies = RigidBodySet::new();
+    let mut colliders = ColliderSet::new();
+    let (entities, errors) = load_world_to_rapier_with(
+        &world,
+        &options,
+        &mut bodies,
+        &mut colliders,
+        &mut IslandManager::new(),
+        &mut ImpulseJointSet::new(),
+    )
+    .unwrap();
+    assert!(errors.is_empty());
+
+    let SpawnedWorldEntityData::PhysicsBody((handle, _)) = entities["Ball"].data else {
+        panic!("the ball should be spawned as a body");
+    };
+    let translation = bodies[handle].translation();
+    assert!((translation.x - 2.0).abs() < EPSILON);
+    assert!((translation.y - 8.0).abs() < EPSILON);
+
+    let (_, collider) = colliders.iter().next().unwrap();
+    let radius = collider.shape().as_ball().unwrap().radius;
+    assert!((radius - 1.0).abs() < EPSILON);
+    assert!((collider.translation() - translation).norm() < EPSILON);
+}
@@ -0,0 +1,67 @@
+use common::{
+    builder::{translation, SceneWorldBuilder},
+    entities::physics::CollisionShapeData,
+    resources::physics::ConcavePolygonCollisionShapeData,
+    EntityData, ResourceData,
+};
+use rapier_godot_scene_loader::{load_world_to_rapier, LoaderError, WorldStats};
+
+#[test]
+fn counts_bodies_and_colliders() {
+    let world = SceneWorldBuilder::new()
+        .add_static_body("Ground", translation([0.0, -1.0, 0.0]))
+        .with_box_collider([20.0, 1.0, 20.0])
+        .add_rigid_body("Ball", translation([0.0, 5.0, 0.0]))
+        .with_sphere_collider(0.5)
+        .add_rigid_body("Crate", translation([2.0, 5.0, 0.0]))
+        .with_box_collider([1.0, 1.0, 1.0])
+        .add_area("Trigger", translation([3.0, 0.0, 0.0]))
+        .with_box_collider([2.0, 2.0, 2.0])
+        .build();
+
+    let loaded = load_world_to_rapier(&world, None).unwrap();
+    assert!(loaded.errors.is_empty());
+
+    let stats = WorldStats::from_entities(&loaded.entities, &loaded.colliders);
+    assert_eq!(
+        stats,
+        WorldStats {
+            dynamic_bodies: 2,
+            static_bodies: 1,
+            kinematic_bodies: 0,
+            colliders: 4,
+            sensors: 1,
+        }
+    );
+}
+
+#[test]
+fn skips_concave_shapes_with_partial_vertices() {
+    let mut builder = SceneWorldBuilder::new();
+    let ramp = builder.add_resource(ResourceData::ConcavePolygonCollisionShape(
+        ConcavePolygonCollisionShapeData {
+            data: vec![0.0, 0.0, 0.0, 1.0],
+        },
+    ));
+    let world = builder
+        .add_static_body("Ground", translation([0.0, -1.0, 0.0]))
+        .with_box_collider([20.0, 1.0, 20.0])
+        .with_child(
+            "Ground/Ramp",
+            EntityData::CollisionShape3D(CollisionShapeData {
+                shape: ramp.clone(),
+                transform: translation([0.0; 3]).to_vec(),
+                disabled: None,
+            }),
+        )
+        .build();
+
+    let loaded = load_world_to_rapier(&world, None).unwrap();
+    assert!(matches!(
+        loaded.errors.as_slice(),
+        [LoaderError::InvalidResource { resource, .. }] if *resource == ramp
+    ));
+
+    let stats = WorldStats::from_entities(&loaded.entities, &loaded.colliders);
+    assert_eq!(stats.colliders, 1);
+}
@@ -0,0 +1,165 @@
+#[cfg(feature = "rapier3d-f64")]
+extern crate rapier3d_f64 as rapier3d;
+
+use common::{
+    builder::{translation, SceneWorldBuilder},
+    entities::physics::{KinematicBodyData, PinJointData},
+    metadata::MetadataValue,
+    EntityData, SceneWorld,
+};
+use rapier3d::{dynamics::IntegrationParameters, math::Real, na::Vector3};
+use rapier_godot_scene_loader::{
+    load_world_to_rapier, step_kinematic_bodies, step_world, LoaderError, RapierWorld,
+    SpawnedWorldEntityData,
+};
+
+#[test]
+fn dropped_ball_comes_to_rest_on_the_ground() {
+    let world = SceneWorldBuilder::new()
+        .add_static_body("Ground", translation([0.0, -0.5, 0.0]))
+        .with_box_collider([20.0, 1.0, 20.0])
+        .add_rigid_body("Ball", translation([0.0, 3.0, 0.0]))
+        .with_sphere_collider(0.5)
+        .build();
+
+    let RapierWorld {
+        mut bodies,
+        mut colliders,
+        mut islands,
+        mut impulse_joints,
+        entities,
+        errors,
+        ..
+    } = load_world_to_rapier(&world, None).unwrap();
+    assert!(errors.is_empty());
+
+    step_world(
+        &mut bodies,
+        &mut colliders,
+        &mut islands,
+        &mut impulse_joints,
+        Vector3::new(0.0, -9.81, 0.0),
+        240,
+    );
+
+    let SpawnedWorldEntityData::PhysicsBody((handle, _)) = entities["Ball"].data else {
+        panic!("the ball should be spawned as a body");
+    };
+    let height = bodies[handle].translation().y;
+    assert!((height - 0.5).abs() < 0.05, "ball rests at {}", height);
+}
+
+/// A kinematic platform moving along Z at 1.5 units per second.
+fn moving_platform() -> SceneWorldBuilder {
+    let platform = EntityData::KinematicBody3D(KinematicBodyData {
+        transform: translation([0.0, 1.0, 0.0]).to_vec(),
+        visible: None,
+        linear_velocity: Some([0.0, 0.0, 1.5]),
+    });
+    SceneWorldBuilder::new().add_entity("Platform", platform)
+}
+
+/// How far the platform moves in a single step of `dt` seconds.
+fn platform_step(world: &SceneWorld, dt: Real) -> Vector3<Real> {
+    let RapierWorld {
+        mut bodies,
+        mut colliders,
+        mut islands,
+        mut impulse_joints,
+        entities,
+        errors,
+        ..
+    } = load_world_to_rapier(world, None).unwrap();
+    assert!(errors.is_empty());
+
+    let SpawnedWorldEntityData::PhysicsBody((handle, _)) = entities["Platform"].data else {
+        panic!("the platform should be spawned as a body");
+    };
+    let start = *bodies[handle].translation();
+
+    step_kinematic_bodies(&entities, &mut bodies, dt);
+    step_world(
+        &mut bodies,
+        &mut colliders,
+        &mut islands,
+        &mut impulse_joints,
+        Vector3::new(0.0, -9.81, 0.0),
+        1,
+    );
+
+    bodies[handle].translation() - start
+}
+
+#[test]
+fn kinematic_platform_moves_by_its_velocity() {
+    let world = moving_platform().with_box_collider([2.0, 0.2, 2.0]).build();
+
+    let dt = IntegrationParameters::default().dt;
+    let moved = platform_step(&world, dt);
+    assert!((moved - Vector3::new(0.0, 0.0, 1.5 * dt)).norm() < 1.0e-5);
+}
+
+#[test]
+fn platform_overridden_as_kinematic_still_moves() {
+    let world = moving_platform()
+        .with_metadata(
+            "rigid_body_type",
+            MetadataValue::String("kinematic".to_owned()),
+        )
+        .with_box_collider([2.0, 0.2, 2.0])
+        .build();
+
+    let dt = IntegrationParameters::default().dt;
+    let moved = platform_step(&world, dt);
+    assert!((moved - Vector3::new(0.0, 0.0, 1.5 * dt)).norm() < 1.0e-5);
+}
+
+#[test]
+fn one_sided_pin_joint_holds_the_body_in_place() {
+    let pin = |node_a: Option<&str>| {
+        EntityData::PinJoint3D(PinJointData {
+            transform: translation([0.0, 3.0, 0.0]).to_vec(),
+            node_a: node_a.map(|x| x.to_owned()),
+            node_b: None,
+        })
+    };
+    let world = SceneWorldBuilder::new()
+        .add_rigid_body("Ball", translation([0.0, 3.0, 0.0]))
+        .with_sphere_collider(0.5)
+        .add_entity("Pin", pin(Some("Ball")))
+        .add_entity("Loose", pin(None))
+        .build();
+
+    let RapierWorld {
+        mut bodies,
+        mut colliders,
+        mut islands,
+        mut impulse_joints,
+        entities,
+        errors,
+        ..
+    } = load_world_to_rapier(&world, None).unwrap();
+    assert!(matches!(
+        errors.as_slice(),
+        [LoaderError::InvalidJoint { joint, .. }] if joint == "Loose"
+    ));
+    assert!(matches!(
+        entities["Pin"].data,
+        SpawnedWorldEntityData::Joint((_, Some(_)))
+    ));
+
+    step_world(
+        &mut bodies,
+        &mut colliders,
+        &mut islands,
+        &mut impulse_joints,
+        Vector3::new(0.0, -9.81, 0.0),
+        120,
+    );
+
+    let SpawnedWorldEntityData::PhysicsBody((handle, _)) = entities["Ball"].data else {
+        panic!("the ball should be spawned as a body");
+    };
+    let height = bodies[handle].translation().y;
+    assert!((height - 3.0).abs() < 0.05, "ball hangs at {}", height);
+}
@@ -0,0 +1,117 @@
+#[cfg(feature = "rapier3d-f64")]
+extern crate rapier3d_f64 as rapier3d;
+
+use rapier3d::{
+    math::Real,
+    na::{UnitQuaternion, Vector3},
+};
+use rapier_godot_scene_loader::NodeTransform;
+
+const EPSILON: Real = 1.0e-4;
+
+#[test]
+fn from_parts_round_trips_through_from_matrix() {
+    let translation = Vector3::new(1.0, -2.0, 3.5);
+    let rotation = UnitQuaternion::from_euler_angles(0.3, -0.7, 1.2);
+    let scale = Vector3::new(1.5, 1.5, 1.5);
+
+    let built = NodeTransform::from_parts(translation, rotation, scale);
+    let parsed = NodeTransform::from_matrix(&built.matrix);
+
+    assert!((parsed.translation - translation).norm() < EPSILON);
+    assert!(parsed.rotation.angle_to(&rotation) < EPSILON);
+    assert!((parsed.scale() - scale).norm() < EPSILON);
+    assert!((parsed.matrix - built.matrix).norm() < EPSILON);
+
+    let (roll, pitch, yaw) = parsed.euler_angles();
+    assert!((roll - 0.3).abs() < EPSILON);
+    assert!((pitch + 0.7).abs() < EPSILON);
+    assert!((yaw - 1.2).abs() < EPSILON);
+}
+
+#[test]
+fn from_matrix_round_trips_through_from_parts() {
+    let original = NodeTransform::from_parts(
+        Vector3::new(-4.0, 0.5, 0.0),
+        UnitQuaternion::from_euler_angles(-1.0, 0.2, 0.4),
+        Vector3::new(1.0, 1.0, 1.0),
+    );
+    let parsed = NodeTransform::from_matrix(&original.matrix);
+    let rebuilt = NodeTransform::from_parts(parsed.translation, parsed.rotation, parsed.scale());
+
+    assert!((rebuilt.matrix - original.matrix).norm() < EPSILON);
+}
+
+#[test]
+fn rotation_ignores_non_uniform_scale() {
+    let rotation = UnitQuaternion::from_euler_angles(0.5, 0.25, -0.8);
+    let scale = Vector3::new(2.0, 0.5, 3.0);
+
+    let parsed = NodeTransform::from_matrix(
+        &NodeTransform::from_parts(Vector3::zeros(), rotation, scale).matrix,
+    );
+
+    assert!(parsed.rotation.angle_to(&rotation) < EPSILON);
+    assert!((parsed.scale() - scale).norm() < EPSILON);
+    assert!(!parsed.is_mirrored());
+}
+
+#[test]
+fn rotation_of_doubled_and_rotated_matrix() {
+    let rotation = UnitQuaternion::from_euler_angles(0.0, 1.0, 0.0);
+    let scale = Vector3::new(2.0, 2.0, 2.0);
+
+    let parsed = NodeTransform::from_matrix(
+        &NodeTransform::from_parts(Vector3::new(1.0, 2.0, 3.0), rotation, scale).matrix,
+    );
+
+    assert!(parsed.rotation.angle_to(&rotation) < EPSILON);
+    assert!((parsed.scale() - scale).norm() < EPSILON);
+}
+
+#[test]
+fn mirrored_matrix_keeps_rotation_and_negates_scale() {
+    let rotation = UnitQuaternion::from_euler_angles(-0.4, 0.9, 0.1);
+    let scale = Vector3::new(-1.0, -2.0, -1.5);
+
+    let original = NodeTransform::from_parts(Vector3::new(0.0, 5.0, 0.0), rotation, scale);
+    let parsed = NodeTransform::from_matrix(&original.matrix);
+
+    assert!(parsed.is_mirrored());
+    assert!(parsed.rotation.angle_to(&rotation) < EPSILON);
+    assert!((parsed.scale() - scale).norm() < EPSILON);
+
+    let rebuilt = NodeTransform::from_parts(parsed.translation, parsed.rotation, parsed.scale());
+    assert!((rebuilt.matrix - original.matrix).norm() < EPSILON);
+}
+
+#[test]
+fn godot_transform_layouts_are_equivalent() {
+    let basis_and_origin = [
+        0.0, 0.0, -2.0, // x
+        0.0, 2.0, 0.0, // y
+        2.0, 0.0, 0.0, // z
+        3.0, -1.0, 4.0,
+    ];
+    let matrix = [
+        0.0, 0.0, -2.0, 0.0, //
+        0.0, 2.0, 0.0, 0.0, //
+        2.0, 0.0, 0.0, 0.0, //
+        3.0, -1.0, 4.0, 1.0,
+    ];
+
+    let short = NodeTransform::from_godot_transform(&basis_and_origin).unwrap();
+    let full = NodeTransform::from_godot_transform(&matrix).unwrap();
+
+    assert_eq!(short.matrix, full.matrix);
+    assert_eq!(short.translation, full.translation);
+    assert_eq!(short.rotation, full.rotation);
+    assert_eq!(short.translation, Vector3::new(3.0, -1.0, 4.0));
+    assert!((short.scale() - Vector3::new(2.0, 2.0, 2.0)).norm() < EPSILON);
+}
+
+#[test]
+fn godot_transform_rejects_other_lengths() {
+    let err = NodeTransform::from_godot_transform(&[1.0; 9]).unwrap_err();
+    assert_eq!(err.len, 9);
+}